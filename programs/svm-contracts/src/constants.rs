@@ -2,22 +2,103 @@ use anchor_lang::prelude::*;
 
 // Space constants for GlobalState
 pub const GLOBAL_STATE_SEED: &[u8] = b"global_state";
+pub const ESCROW_SEED: &[u8] = b"escrow";
 pub const DISCRIMINATOR_SIZE: usize = 8;
 pub const PUBKEY_SIZE: usize = 32;
 pub const BOOL_SIZE: usize = 1;
 pub const VEC_LENGTH_SIZE: usize = 4;
 pub const STRING_LENGTH_SIZE: usize = 4; // anchor serializes String as vec<u8> with 4-byte len
 pub const MAX_SUPPORTED_TOKEN_MINTS: usize = 10;
+pub const MAX_APPROVERS: usize = 5;
+pub const MAX_OWNER_HISTORY: usize = 5;
+// Every instruction deserializes GlobalState, so the list is kept short
+pub const MAX_BLOCKLIST_SIZE: usize = 16;
+pub const REWARD_APPROVAL_SEED: &[u8] = b"reward_approval";
+// Ids can exceed the 32-byte seed limit, so the registry is keyed by their hash
+pub const QUEST_ID_SEED: &[u8] = b"quest_id";
 pub const REWARD_CLAIMED_SPACE: usize = DISCRIMINATOR_SIZE + // discriminator
+    PUBKEY_SIZE + // quest (pubkey)
+    PUBKEY_SIZE + // winner (pubkey)
+    U64_SIZE + // reward_amount
+    BOOL_SIZE + // claimed
+    U8_SIZE + // bump
+    U8_SIZE + // reason
+    U8_SIZE; // version
+
+// Records written before bump, reason and version were added stop after `claimed`
+pub const LEGACY_REWARD_CLAIMED_SPACE: usize = DISCRIMINATOR_SIZE + // discriminator
     PUBKEY_SIZE + // quest (pubkey)
     PUBKEY_SIZE + // winner (pubkey)
     U64_SIZE + // reward_amount
     BOOL_SIZE; // claimed
+pub const REWARD_CLAIMED_VERSION: u8 = 1;
 
 // Space constants for Quest
 pub const MAX_QUEST_ID_LENGTH: usize = 36;
 pub const U64_SIZE: usize = 8;
 pub const U32_SIZE: usize = 4;
+pub const U16_SIZE: usize = 2;
+pub const U8_SIZE: usize = 1;
+pub const BPS_DENOMINATOR: u64 = 10_000;
+// Delay after the deadline before remaining rewards can be claimed (7 days)
+pub const REMAINING_CLAIM_DELAY_SECONDS: i64 = 604_800;
+// Deadline passed to create_quest to use the platform default duration
+pub const DEFAULT_DEADLINE_SENTINEL: i64 = 0;
+// Furthest a quest deadline may sit in the future (100 years); keeps
+// deadline + REMAINING_CLAIM_DELAY_SECONDS far from overflowing
+pub const MAX_DEADLINE_HORIZON_SECONDS: i64 = 100 * 365 * 86_400;
+
+// Conservative compute cost of paying one batch winner (PDA derivation,
+// record creation and token transfer CPIs)
+pub const BATCH_COMPUTE_UNITS_PER_WINNER: u64 = 40_000;
+// Largest batch whose winner accounts fit a legacy transaction alongside a
+// compute budget instruction; 7 winners need at most 280k units
+pub const MAX_BATCH_SIZE: usize = 7;
+
+// Audit log ring buffer; capacity keeps a full read within the return data limit
+pub const AUDIT_LOG_SEED: &[u8] = b"audit_log";
+pub const AUDIT_LOG_CAPACITY: usize = 16;
+pub const AUDIT_ENTRY_SIZE: usize = U8_SIZE + // action_code
+    PUBKEY_SIZE + // actor
+    U64_SIZE; // timestamp
+
+// Audit action codes
+pub const ACTION_PAUSE: u8 = 1;
+pub const ACTION_UNPAUSE: u8 = 2;
+pub const ACTION_SET_OWNER: u8 = 3;
+pub const ACTION_SEND_REWARD: u8 = 4;
+pub const ACTION_ADD_SUPPORTED_TOKEN: u8 = 5;
+pub const ACTION_REMOVE_SUPPORTED_TOKEN: u8 = 6;
+pub const ACTION_SET_SUPPORTED_TOKENS: u8 = 7;
+pub const ACTION_PAUSE_CLAIMS: u8 = 8;
+pub const ACTION_UNPAUSE_CLAIMS: u8 = 9;
+pub const ACTION_SET_QUEST_STATUS: u8 = 10;
+pub const ACTION_PAUSE_QUESTS: u8 = 11;
+pub const ACTION_UNPAUSE_QUESTS: u8 = 12;
+pub const ACTION_FREEZE_QUEST: u8 = 13;
+pub const ACTION_UNFREEZE_QUEST: u8 = 14;
+pub const ACTION_SET_MAX_WINNERS: u8 = 15;
+pub const ACTION_RECOUNT: u8 = 16;
+pub const ACTION_UPDATE_CONFIG: u8 = 17;
+pub const ACTION_SET_LARGE_REWARD_APPROVAL: u8 = 18;
+pub const ACTION_BLOCK_ADDRESS: u8 = 19;
+pub const ACTION_UNBLOCK_ADDRESS: u8 = 20;
+pub const ACTION_CLOSE_REWARD_RECORDS: u8 = 21;
+pub const ACTION_MIGRATE: u8 = 22;
+pub const ACTION_CLOSE_REWARD_CLAIMED: u8 = 23;
+pub const ACTION_CLAIM_REMAINING: u8 = 24;
+pub const ACTION_CANCEL_LARGE_REWARD: u8 = 25;
+pub const ACTION_INITIALIZE_AUDIT_LOG: u8 = 26;
+
+// RewardClaimed reason codes
+pub const REWARD_REASON_WINNER: u8 = 0;
+pub const REWARD_REASON_WEIGHTED: u8 = 1;
+pub const REWARD_REASON_EQUAL_SPLIT: u8 = 2;
+
+// Programs not otherwise linked since only the `token` feature is enabled
+pub const ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey =
+    pubkey!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+pub const TOKEN_2022_PROGRAM_ID: Pubkey = pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
 
 // Calculated space constants
 pub const GLOBAL_STATE_SPACE: usize = DISCRIMINATOR_SIZE + // discriminator
@@ -25,7 +106,20 @@ pub const GLOBAL_STATE_SPACE: usize = DISCRIMINATOR_SIZE + // discriminator
     BOOL_SIZE + // paused bool
     VEC_LENGTH_SIZE + // vec len for supported_token_mints
     (PUBKEY_SIZE * MAX_SUPPORTED_TOKEN_MINTS) + // space for up to 10 token mints
-    U32_SIZE; // quest_count
+    U32_SIZE + // quest_count
+    U64_SIZE + // min_quest_duration_seconds
+    VEC_LENGTH_SIZE + // vec len for blocklist (entries are realloc'd on demand)
+    BOOL_SIZE + // enforce_supported_mint_on_payout
+    U64_SIZE + // default_quest_duration_seconds
+    U64_SIZE + // global_min_reward
+    U64_SIZE + // large_reward_threshold
+    VEC_LENGTH_SIZE + (PUBKEY_SIZE * MAX_APPROVERS) + // approvers
+    U8_SIZE + // required_approvals
+    BOOL_SIZE + // claims_paused
+    BOOL_SIZE + // enforce_min_reward_feasibility
+    VEC_LENGTH_SIZE + (PUBKEY_SIZE * MAX_OWNER_HISTORY) + // owner_history
+    U8_SIZE + // max_quest_id_length
+    BOOL_SIZE; // audit_log_initialized
 
 pub const QUEST_SPACE: usize = DISCRIMINATOR_SIZE + // discriminator
     STRING_LENGTH_SIZE + MAX_QUEST_ID_LENGTH + // id string (max)
@@ -37,7 +131,21 @@ pub const QUEST_SPACE: usize = DISCRIMINATOR_SIZE + // discriminator
     BOOL_SIZE + // is_active
     U32_SIZE + // total_winners
     U64_SIZE + // total_reward_distributed
-    U32_SIZE; // max_winners
+    U32_SIZE + // max_winners
+    BOOL_SIZE + // paused
+    U64_SIZE + // reserve_amount
+    U16_SIZE + // max_single_reward_bps
+    BOOL_SIZE + // cancelled
+    BOOL_SIZE + // finalized
+    BOOL_SIZE + // owner_frozen
+    U64_SIZE + // weighted_pool
+    U64_SIZE + // weighted_distributed
+    U16_SIZE + // bonus_bps
+    U64_SIZE + // bonus_until
+    U64_SIZE + // first_reward_at
+    U64_SIZE + // completed_at
+    U8_SIZE + // leftover_policy
+    BOOL_SIZE; // records_closed
 
 #[account]
 pub struct GlobalState {
@@ -45,6 +153,25 @@ pub struct GlobalState {
     pub paused: bool,
     pub supported_token_mints: Vec<Pubkey>,
     pub quest_count: u32,
+    pub min_quest_duration_seconds: i64,
+    pub blocklist: Vec<Pubkey>,
+    pub enforce_supported_mint_on_payout: bool,
+    pub default_quest_duration_seconds: i64,
+    pub global_min_reward: u64,
+    pub large_reward_threshold: u64,
+    pub approvers: Vec<Pubkey>,
+    pub required_approvals: u8,
+    /// Blocks payouts and remaining-reward claims while quest creation stays open
+    pub claims_paused: bool,
+    /// Rejects, rather than only logs, payouts that leave too little for the
+    /// open winner slots to each get `global_min_reward`
+    pub enforce_min_reward_feasibility: bool,
+    /// Previous owners, oldest first, capped at `MAX_OWNER_HISTORY`
+    pub owner_history: Vec<Pubkey>,
+    /// Soft cap on quest id length, at most the space-derived `MAX_QUEST_ID_LENGTH`
+    pub max_quest_id_length: u8,
+    /// Set by `initialize_audit_log`; privileged instructions must pass the log from then on
+    pub audit_log_initialized: bool,
 }
 
 #[account]
@@ -59,6 +186,105 @@ pub struct Quest {
     pub total_winners: u32,
     pub total_reward_distributed: u64,
     pub max_winners: u32,
+    pub paused: bool,
+    pub reserve_amount: u64,
+    pub max_single_reward_bps: u16,
+    pub cancelled: bool,
+    pub finalized: bool,
+    pub owner_frozen: bool,
+    pub weighted_pool: u64,
+    pub weighted_distributed: u64,
+    pub bonus_bps: u16,
+    pub bonus_until: i64,
+    /// Unix timestamp of the first payout; 0 until then
+    pub first_reward_at: i64,
+    /// Unix timestamp the quest ran out of winner slots or funds; 0 until then
+    pub completed_at: i64,
+    pub leftover_policy: LeftoverPolicy,
+    /// Set once any reward record is closed; the quest can then never reopen
+    /// since those winners could be paid again
+    pub records_closed: bool,
+}
+
+/// Lifecycle state of a quest, derived from its flags. `Paused` overlays an
+/// active quest and is toggled by `pause_quests`, outside the transition table.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum QuestStatus {
+    Active,
+    Paused,
+    Inactive,
+    Cancelled,
+    Completed,
+}
+
+impl QuestStatus {
+    pub fn of(quest: &Quest) -> Self {
+        if quest.finalized {
+            Self::Completed
+        } else if quest.cancelled {
+            Self::Cancelled
+        } else if !quest.is_active {
+            Self::Inactive
+        } else if quest.paused {
+            Self::Paused
+        } else {
+            Self::Active
+        }
+    }
+}
+
+/// What `claim_remaining_reward` does with funds no winner received.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum LeftoverPolicy {
+    Refund,
+    Burn,
+}
+
+pub const AUDIT_LOG_SPACE: usize = DISCRIMINATOR_SIZE + // discriminator
+    U16_SIZE + // head
+    U64_SIZE + // total_recorded
+    VEC_LENGTH_SIZE + (AUDIT_ENTRY_SIZE * AUDIT_LOG_CAPACITY); // entries
+
+#[account]
+pub struct AuditLog {
+    /// Slot the next entry is written to once the buffer has wrapped
+    pub head: u16,
+    pub total_recorded: u64,
+    pub entries: Vec<AuditEntry>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct AuditEntry {
+    pub action_code: u8,
+    pub actor: Pubkey,
+    pub timestamp: i64,
+}
+
+pub const REWARD_APPROVAL_SPACE: usize = DISCRIMINATOR_SIZE + // discriminator
+    PUBKEY_SIZE + // quest
+    PUBKEY_SIZE + // winner
+    U64_SIZE + // amount
+    PUBKEY_SIZE + // proposer
+    VEC_LENGTH_SIZE + (PUBKEY_SIZE * MAX_APPROVERS); // approvals
+
+/// Approvals collected for a payout above `large_reward_threshold`.
+#[account]
+pub struct RewardApproval {
+    pub quest: Pubkey,
+    pub winner: Pubkey,
+    pub amount: u64,
+    /// Paid the rent, so receives it back when the approval is used or cancelled
+    pub proposer: Pubkey,
+    pub approvals: Vec<Pubkey>,
+}
+
+pub const QUEST_ID_REGISTRY_SPACE: usize = DISCRIMINATOR_SIZE + // discriminator
+    PUBKEY_SIZE; // quest
+
+/// Reserves a quest id so no two quests can share it.
+#[account]
+pub struct QuestIdRegistry {
+    pub quest: Pubkey,
 }
 
 #[account]
@@ -67,4 +293,120 @@ pub struct RewardClaimed {
     pub winner: Pubkey,
     pub reward_amount: u64,
     pub claimed: bool,
+    pub bump: u8,
+    /// One of the `REWARD_REASON_*` codes
+    pub reason: u8,
+    /// `REWARD_CLAIMED_VERSION` for records in the current layout
+    pub version: u8,
+}
+
+/// `RewardClaimed` as it was laid out before versioning, read by
+/// `migrate_reward_claimed`.
+#[derive(AnchorDeserialize)]
+pub struct LegacyRewardClaimed {
+    pub quest: Pubkey,
+    pub winner: Pubkey,
+    pub reward_amount: u64,
+    pub claimed: bool,
+}
+
+/// Read-only snapshot of every owner-settable parameter, kept separate from
+/// `GlobalState` so its layout can evolve independently. The blocklist is left
+/// out to keep the view within the return data limit; read it from the account.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ConfigView {
+    pub owner: Pubkey,
+    pub paused: bool,
+    pub supported_token_mints: Vec<Pubkey>,
+    pub min_quest_duration_seconds: i64,
+    pub enforce_supported_mint_on_payout: bool,
+    pub default_quest_duration_seconds: i64,
+    pub global_min_reward: u64,
+    pub large_reward_threshold: u64,
+    pub approvers: Vec<Pubkey>,
+    pub required_approvals: u8,
+    pub claims_paused: bool,
+    pub enforce_min_reward_feasibility: bool,
+    pub max_quest_id_length: u8,
+    pub audit_log_initialized: bool,
+}
+
+/// Everything the admin panel needs for a single quest in one read.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct QuestContext {
+    pub global: GlobalSummary,
+    pub quest: Quest,
+    pub reward_claimed: Option<RewardClaimed>,
+}
+
+/// Fixed-size slice of `GlobalState` for `QuestContext`; the full account's
+/// lists would push the view past the return data limit.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct GlobalSummary {
+    pub owner: Pubkey,
+    pub paused: bool,
+    pub claims_paused: bool,
+    pub quest_count: u32,
+    pub global_min_reward: u64,
+    pub large_reward_threshold: u64,
+    pub required_approvals: u8,
+    /// The quest's mint is still in `supported_token_mints`
+    pub mint_supported: bool,
+}
+
+/// Every contract-wide pause flag, for status banners.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct PauseStatus {
+    pub paused: bool,
+    pub claims_paused: bool,
+    pub any_paused: bool,
+}
+
+/// End-of-campaign payout summary for a quest.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct QuestReport {
+    pub amount: u64,
+    pub distributed: u64,
+    pub remaining: u64,
+    pub winner_count: u32,
+    pub max_winners: u32,
+    /// Seconds per winner from first payout to completion, once completed
+    pub average_payout_interval: Option<i64>,
+}
+
+/// Dry run of a `send_reward_batch` against the quest's current state.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct BatchPreview {
+    /// Total paid out, early bonus included
+    pub total: u64,
+    /// The quest's unlocked undistributed amount and its escrow both cover the total
+    pub fits_escrow: bool,
+    pub fits_slots: bool,
+    /// The batch fits and would use up the quest's funds or winner slots
+    pub would_complete: bool,
+}
+
+/// Invariant checks for a quest, reported rather than enforced so monitors
+/// can poll without the call failing.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct HealthReport {
+    pub distributed_within_amount: bool,
+    pub winners_within_max: bool,
+    pub escrow_solvent: bool,
+    /// The escrow is the quest's, holds its mint and is owned by the program
+    pub escrow_linked: bool,
+    pub healthy: bool,
+}
+
+/// A winner's reward record together with its PDA derivation, so light
+/// clients can verify the address themselves.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct WinnerReward {
+    pub winner: Pubkey,
+    pub quest: Pubkey,
+    pub reward_amount: u64,
+    pub claimed: bool,
+    pub pda: Pubkey,
+    pub bump: u8,
+    pub reason: u8,
 }
@@ -1,9 +1,33 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use anchor_lang::system_program;
+use anchor_spl::token::spl_token::state::AccountState;
+use anchor_spl::token::{
+    self, Burn, CloseAccount, InitializeAccount3, Mint, Token, TokenAccount, Transfer,
+};
+use solana_program::compute_units;
+use solana_program::hash::hash;
 mod constants;
 use constants::RewardClaimed;
 use constants::{
-    GlobalState, Quest, GLOBAL_STATE_SEED, GLOBAL_STATE_SPACE, QUEST_SPACE, REWARD_CLAIMED_SPACE,
+    AuditEntry, AuditLog, BatchPreview, ConfigView, GlobalState, GlobalSummary, HealthReport,
+    LeftoverPolicy, LegacyRewardClaimed, PauseStatus, Quest, QuestContext, QuestIdRegistry,
+    QuestReport, QuestStatus, RewardApproval, WinnerReward, ACTION_ADD_SUPPORTED_TOKEN,
+    ACTION_BLOCK_ADDRESS, ACTION_CANCEL_LARGE_REWARD, ACTION_CLAIM_REMAINING,
+    ACTION_CLOSE_REWARD_CLAIMED, ACTION_CLOSE_REWARD_RECORDS, ACTION_FREEZE_QUEST,
+    ACTION_INITIALIZE_AUDIT_LOG, ACTION_MIGRATE, ACTION_PAUSE, ACTION_PAUSE_CLAIMS,
+    ACTION_PAUSE_QUESTS, ACTION_RECOUNT, ACTION_REMOVE_SUPPORTED_TOKEN, ACTION_SEND_REWARD,
+    ACTION_SET_LARGE_REWARD_APPROVAL, ACTION_SET_MAX_WINNERS, ACTION_SET_OWNER,
+    ACTION_SET_QUEST_STATUS, ACTION_SET_SUPPORTED_TOKENS, ACTION_UNBLOCK_ADDRESS,
+    ACTION_UNFREEZE_QUEST, ACTION_UNPAUSE, ACTION_UNPAUSE_CLAIMS, ACTION_UNPAUSE_QUESTS,
+    ACTION_UPDATE_CONFIG, ASSOCIATED_TOKEN_PROGRAM_ID, AUDIT_LOG_CAPACITY, AUDIT_LOG_SEED,
+    AUDIT_LOG_SPACE, BATCH_COMPUTE_UNITS_PER_WINNER, BPS_DENOMINATOR, DEFAULT_DEADLINE_SENTINEL,
+    DISCRIMINATOR_SIZE, ESCROW_SEED, GLOBAL_STATE_SEED, GLOBAL_STATE_SPACE,
+    LEGACY_REWARD_CLAIMED_SPACE, MAX_APPROVERS, MAX_BATCH_SIZE, MAX_BLOCKLIST_SIZE,
+    MAX_DEADLINE_HORIZON_SECONDS, MAX_OWNER_HISTORY, MAX_QUEST_ID_LENGTH,
+    MAX_SUPPORTED_TOKEN_MINTS, PUBKEY_SIZE, QUEST_ID_REGISTRY_SPACE, QUEST_ID_SEED, QUEST_SPACE,
+    REMAINING_CLAIM_DELAY_SECONDS, REWARD_APPROVAL_SEED, REWARD_APPROVAL_SPACE,
+    REWARD_CLAIMED_SPACE, REWARD_CLAIMED_VERSION, REWARD_REASON_EQUAL_SPLIT,
+    REWARD_REASON_WEIGHTED, REWARD_REASON_WINNER, TOKEN_2022_PROGRAM_ID,
 };
 
 declare_id!("5cukA1JtwmSH7gboD3X3VGfgqQ4KE6sN5PPNctKLhhh8");
@@ -12,12 +36,47 @@ declare_id!("5cukA1JtwmSH7gboD3X3VGfgqQ4KE6sN5PPNctKLhhh8");
 pub mod svm_contracts {
     use super::*;
 
-    pub fn initialize(ctx: Context<Initialize>, supported_token_mints: Vec<Pubkey>) -> Result<()> {
+    pub fn initialize<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Initialize<'info>>,
+        supported_token_mints: Vec<Pubkey>,
+    ) -> Result<()> {
+        // Only the upgrade authority may bootstrap ownership, so nobody can
+        // front-run initialize after deployment
+        require!(
+            ctx.accounts.program_data.upgrade_authority_address == Some(ctx.accounts.owner.key()),
+            CustomError::UnauthorizedInit
+        );
+
+        // Each listed mint is passed, in order, as a remaining account so it can
+        // be checked to be an initialized SPL mint
+        require!(
+            ctx.remaining_accounts.len() == supported_token_mints.len(),
+            CustomError::InvalidTokenMint
+        );
+        for (mint, mint_info) in supported_token_mints.iter().zip(ctx.remaining_accounts) {
+            require_keys_eq!(*mint, mint_info.key(), CustomError::InvalidTokenMint);
+            Account::<'info, Mint>::try_from(mint_info)
+                .map_err(|_| CustomError::InvalidTokenMint)?;
+        }
+
         let global_state = &mut ctx.accounts.global_state;
         global_state.owner = ctx.accounts.owner.key();
         global_state.paused = false;
         global_state.supported_token_mints = supported_token_mints;
         global_state.quest_count = 0;
+        global_state.min_quest_duration_seconds = 0;
+        global_state.blocklist = Vec::new();
+        global_state.enforce_supported_mint_on_payout = false;
+        global_state.default_quest_duration_seconds = 0;
+        global_state.global_min_reward = 0;
+        global_state.large_reward_threshold = 0;
+        global_state.approvers = Vec::new();
+        global_state.required_approvals = 0;
+        global_state.claims_paused = false;
+        global_state.enforce_min_reward_feasibility = false;
+        global_state.owner_history = Vec::new();
+        global_state.max_quest_id_length = MAX_QUEST_ID_LENGTH as u8;
+        global_state.audit_log_initialized = false;
         Ok(())
     }
 
@@ -39,6 +98,38 @@ pub mod svm_contracts {
                 .contains(&ctx.accounts.token_mint.key()),
             CustomError::UnsupportedTokenMint
         );
+        require!(
+            id.len() <= usize::from(ctx.accounts.global_state.max_quest_id_length),
+            CustomError::QuestIdTooLong
+        );
+        require!(
+            amount >= ctx.accounts.global_state.global_min_reward,
+            CustomError::BelowMinReward
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        let deadline = if deadline == DEFAULT_DEADLINE_SENTINEL {
+            let default_duration = ctx.accounts.global_state.default_quest_duration_seconds;
+            require!(default_duration > 0, CustomError::InvalidDeadline);
+            now.checked_add(default_duration)
+                .ok_or(CustomError::InvalidDeadline)?
+        } else {
+            deadline
+        };
+        require!(
+            deadline >= 0 && deadline - now <= MAX_DEADLINE_HORIZON_SECONDS,
+            CustomError::InvalidDeadline
+        );
+
+        let min_duration = ctx.accounts.global_state.min_quest_duration_seconds;
+        if min_duration > 0 {
+            let duration = deadline
+                .checked_sub(now)
+                .ok_or(CustomError::QuestTooShort)?;
+            require!(duration >= min_duration, CustomError::QuestTooShort);
+        }
+
+        ctx.accounts.quest_id_registry.quest = ctx.accounts.quest.key();
 
         let quest = &mut ctx.accounts.quest;
         quest.id = id.clone();
@@ -51,6 +142,31 @@ pub mod svm_contracts {
         quest.total_winners = 0;
         quest.total_reward_distributed = 0;
         quest.max_winners = max_winners;
+        quest.paused = false;
+        quest.reserve_amount = 0;
+        quest.max_single_reward_bps = 0;
+        quest.cancelled = false;
+        quest.finalized = false;
+        quest.owner_frozen = false;
+        quest.weighted_pool = 0;
+        quest.weighted_distributed = 0;
+        quest.bonus_bps = 0;
+        quest.bonus_until = 0;
+        quest.first_reward_at = 0;
+        quest.completed_at = 0;
+        quest.leftover_policy = LeftoverPolicy::Refund;
+        quest.records_closed = false;
+
+        let quest_key = ctx.accounts.quest.key();
+        create_escrow_account(
+            &ctx.accounts.creator.to_account_info(),
+            &ctx.accounts.escrow_account.to_account_info(),
+            &ctx.accounts.token_mint.to_account_info(),
+            &ctx.accounts.global_state.to_account_info(),
+            &ctx.accounts.token_program.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            &[ESCROW_SEED, quest_key.as_ref(), &[ctx.bumps.escrow_account]],
+        )?;
 
         // Transfer tokens from creator to escrow account
         let transfer_ctx = CpiContext::new(
@@ -69,257 +185,2700 @@ pub mod svm_contracts {
         Ok(())
     }
 
-    pub fn get_quest_info(ctx: Context<GetQuestInfo>) -> Result<Quest> {
-        Ok((*ctx.accounts.quest).clone())
-    }
-
-    pub fn get_all_quests(ctx: Context<GetAllQuests>) -> Result<Vec<String>> {
-        let global_state = &ctx.accounts.global_state;
-        // NOTE: quests changed to Vec<Pubkey> for consistency. 
-        // This function is deprecated; prefer fetching quest accounts directly client-side.
-        Ok(Vec::new())
-    }
+    pub fn split_quest(ctx: Context<SplitQuest>, new_id: String, split_amount: u64) -> Result<()> {
+        require!(
+            !ctx.accounts.global_state.paused,
+            CustomError::ContractPaused
+        );
+        require!(
+            !ctx.accounts.global_state.claims_paused,
+            CustomError::ClaimsPaused
+        );
+        require!(
+            new_id.len() <= usize::from(ctx.accounts.global_state.max_quest_id_length),
+            CustomError::QuestIdTooLong
+        );
+        require!(
+            split_amount >= ctx.accounts.global_state.global_min_reward,
+            CustomError::BelowMinReward
+        );
 
-    pub fn cancel_quest(ctx: Context<CancelQuest>) -> Result<()> {
         let quest = &mut ctx.accounts.quest;
-
-        require!(quest.is_active, CustomError::QuestNotActive);
         require!(
             quest.creator == ctx.accounts.creator.key(),
-            CustomError::UnauthorizedCancellation
+            CustomError::UnauthorizedQuestUpdate
         );
+        require!(quest.is_active, CustomError::QuestNotActive);
+        require!(!quest.paused, CustomError::QuestPaused);
+        require!(!quest.owner_frozen, CustomError::QuestFrozen);
 
-        let signer_seeds: &[&[&[u8]]] = &[&[GLOBAL_STATE_SEED, &[ctx.bumps.global_state]]];
+        // Only undistributed, unreserved funds can move; what was already paid
+        // out stays accounted for on the original quest
+        let remaining = quest
+            .amount
+            .checked_sub(quest.total_reward_distributed)
+            .ok_or(CustomError::InvariantViolation)?;
+        require!(
+            split_amount > 0 && split_amount <= remaining.saturating_sub(quest.reserve_amount),
+            CustomError::InvalidSplit
+        );
 
-        // Transfer tokens back to creator
+        // Open winner slots follow the funds
+        let open_slots = quest.max_winners.saturating_sub(quest.total_winners);
+        let new_max_winners = u32::try_from(
+            u128::from(open_slots) * u128::from(split_amount) / u128::from(remaining),
+        )
+        .map_err(|_| CustomError::ArithmeticOverflow)?;
+        require!(new_max_winners > 0, CustomError::InvalidSplit);
+
+        quest.amount -= split_amount;
+        quest.max_winners = quest
+            .max_winners
+            .checked_sub(new_max_winners)
+            .ok_or(CustomError::WinnerCountOverflow)?;
+        let deadline = quest.deadline;
+        let token_mint = quest.token_mint;
+        let leftover_policy = quest.leftover_policy;
+
+        let new_quest_key = ctx.accounts.new_quest.key();
+        create_escrow_account(
+            &ctx.accounts.creator.to_account_info(),
+            &ctx.accounts.new_escrow_account.to_account_info(),
+            &ctx.accounts.token_mint.to_account_info(),
+            &ctx.accounts.global_state.to_account_info(),
+            &ctx.accounts.token_program.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            &[
+                ESCROW_SEED,
+                new_quest_key.as_ref(),
+                &[ctx.bumps.new_escrow_account],
+            ],
+        )?;
+
+        let signer_seeds: &[&[&[u8]]] = &[&[GLOBAL_STATE_SEED, &[ctx.bumps.global_state]]];
         let transfer_ctx = CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
             Transfer {
                 from: ctx.accounts.escrow_account.to_account_info(),
-                to: ctx.accounts.creator_token_account.to_account_info(),
+                to: ctx.accounts.new_escrow_account.to_account_info(),
                 authority: ctx.accounts.global_state.to_account_info(),
             },
             signer_seeds,
         );
-        token::transfer(transfer_ctx, ctx.accounts.escrow_account.amount)?;
+        token::transfer(transfer_ctx, split_amount)?;
+
+        ctx.accounts.quest_id_registry.quest = new_quest_key;
+        ctx.accounts.new_quest.set_inner(Quest {
+            id: new_id,
+            creator: ctx.accounts.creator.key(),
+            token_mint,
+            escrow_account: ctx.accounts.new_escrow_account.key(),
+            amount: split_amount,
+            deadline,
+            is_active: true,
+            total_winners: 0,
+            total_reward_distributed: 0,
+            max_winners: new_max_winners,
+            paused: false,
+            reserve_amount: 0,
+            max_single_reward_bps: 0,
+            cancelled: false,
+            finalized: false,
+            owner_frozen: false,
+            weighted_pool: 0,
+            weighted_distributed: 0,
+            bonus_bps: 0,
+            bonus_until: 0,
+            first_reward_at: 0,
+            completed_at: 0,
+            leftover_policy,
+            records_closed: false,
+        });
 
-        quest.is_active = false;
+        let global_state = &mut ctx.accounts.global_state;
+        global_state.quest_count = global_state.quest_count.saturating_add(1);
         Ok(())
     }
 
-    pub fn update_quest_status(ctx: Context<UpdateQuestStatus>, is_active: bool) -> Result<()> {
+    pub fn merge_quests(ctx: Context<MergeQuests>) -> Result<()> {
         require!(
-            ctx.accounts.owner.key() == ctx.accounts.global_state.owner,
-            CustomError::UnauthorizedStatusUpdate
+            !ctx.accounts.global_state.paused,
+            CustomError::ContractPaused
+        );
+        require!(
+            !ctx.accounts.global_state.claims_paused,
+            CustomError::ClaimsPaused
+        );
+        require_keys_neq!(
+            ctx.accounts.quest.key(),
+            ctx.accounts.source_quest.key(),
+            CustomError::InvalidMerge
         );
 
+        let creator = ctx.accounts.creator.key();
         let quest = &mut ctx.accounts.quest;
-        quest.is_active = is_active;
-        Ok(())
-    }
-
-    pub fn add_supported_token(ctx: Context<ModifyToken>) -> Result<()> {
+        let source = &ctx.accounts.source_quest;
         require!(
-            ctx.accounts.owner.key() == ctx.accounts.global_state.owner,
-            CustomError::UnauthorizedTokenModification
+            quest.creator == creator && source.creator == creator,
+            CustomError::UnauthorizedQuestUpdate
+        );
+        require!(
+            quest.token_mint == source.token_mint,
+            CustomError::InvalidMerge
+        );
+        require!(
+            quest.is_active && source.is_active,
+            CustomError::QuestNotActive
+        );
+        require!(!quest.paused && !source.paused, CustomError::QuestPaused);
+        require!(
+            !quest.owner_frozen && !source.owner_frozen,
+            CustomError::QuestFrozen
         );
 
-        let global_state = &mut ctx.accounts.global_state;
-        let token_mint = ctx.accounts.token_mint.key();
+        // History moves along with the funds so the merged quest's counters
+        // still add up against its escrow
+        quest.amount = quest
+            .amount
+            .checked_add(source.amount)
+            .ok_or(CustomError::ArithmeticOverflow)?;
+        quest.total_reward_distributed = quest
+            .total_reward_distributed
+            .checked_add(source.total_reward_distributed)
+            .ok_or(CustomError::ArithmeticOverflow)?;
+        quest.total_winners = quest
+            .total_winners
+            .checked_add(source.total_winners)
+            .ok_or(CustomError::WinnerCountOverflow)?;
+        quest.max_winners = quest
+            .max_winners
+            .checked_add(source.max_winners)
+            .ok_or(CustomError::WinnerCountOverflow)?;
+        quest.reserve_amount = quest
+            .reserve_amount
+            .checked_add(source.reserve_amount)
+            .ok_or(CustomError::ArithmeticOverflow)?;
 
-        require!(
-            !global_state.supported_token_mints.contains(&token_mint),
-            CustomError::TokenAlreadySupported
+        let signer_seeds: &[&[&[u8]]] = &[&[GLOBAL_STATE_SEED, &[ctx.bumps.global_state]]];
+        let transfer_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.source_escrow_account.to_account_info(),
+                to: ctx.accounts.escrow_account.to_account_info(),
+                authority: ctx.accounts.global_state.to_account_info(),
+            },
+            signer_seeds,
         );
+        token::transfer(transfer_ctx, ctx.accounts.source_escrow_account.amount)?;
 
-        global_state.supported_token_mints.push(token_mint);
+        let close_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: ctx.accounts.source_escrow_account.to_account_info(),
+                destination: ctx.accounts.creator.to_account_info(),
+                authority: ctx.accounts.global_state.to_account_info(),
+            },
+            signer_seeds,
+        );
+        token::close_account(close_ctx)?;
+
+        let global_state = &mut ctx.accounts.global_state;
+        global_state.quest_count = global_state.quest_count.saturating_sub(1);
         Ok(())
     }
 
-    pub fn remove_supported_token(ctx: Context<ModifyToken>) -> Result<()> {
+    pub fn transfer_between_quests(ctx: Context<TransferBetweenQuests>, amount: u64) -> Result<()> {
         require!(
-            ctx.accounts.owner.key() == ctx.accounts.global_state.owner,
-            CustomError::UnauthorizedTokenModification
+            !ctx.accounts.global_state.paused,
+            CustomError::ContractPaused
         );
+        require!(
+            !ctx.accounts.global_state.claims_paused,
+            CustomError::ClaimsPaused
+        );
+        require_keys_neq!(
+            ctx.accounts.quest.key(),
+            ctx.accounts.source_quest.key(),
+            CustomError::InvalidQuestTransfer
+        );
+        require!(amount > 0, CustomError::InvalidAmount);
 
-        let global_state = &mut ctx.accounts.global_state;
-        let token_mint = ctx.accounts.token_mint.key();
+        let creator = ctx.accounts.creator.key();
+        let quest = &mut ctx.accounts.quest;
+        let source = &mut ctx.accounts.source_quest;
+        require!(
+            quest.creator == creator && source.creator == creator,
+            CustomError::UnauthorizedQuestUpdate
+        );
+        require!(
+            quest.token_mint == source.token_mint,
+            CustomError::InvalidQuestTransfer
+        );
+        require!(
+            quest.is_active && source.is_active,
+            CustomError::QuestNotActive
+        );
+        require!(!quest.paused && !source.paused, CustomError::QuestPaused);
+        require!(
+            !quest.owner_frozen && !source.owner_frozen,
+            CustomError::QuestFrozen
+        );
+        // Moving funds out is held to the same limits as paying them out
+        ensure_distributable(source, amount)?;
+        require!(
+            ctx.accounts.source_escrow_account.amount >= amount,
+            CustomError::InsufficientRewardBalance
+        );
 
-        let position = global_state
-            .supported_token_mints
+        source.amount = source
+            .amount
+            .checked_sub(amount)
+            .ok_or(CustomError::ArithmeticOverflow)?;
+        quest.amount = quest
+            .amount
+            .checked_add(amount)
+            .ok_or(CustomError::ArithmeticOverflow)?;
+        ensure_quest_invariants(source)?;
+
+        let signer_seeds: &[&[&[u8]]] = &[&[GLOBAL_STATE_SEED, &[ctx.bumps.global_state]]];
+        let transfer_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.source_escrow_account.to_account_info(),
+                to: ctx.accounts.escrow_account.to_account_info(),
+                authority: ctx.accounts.global_state.to_account_info(),
+            },
+            signer_seeds,
+        );
+        token::transfer(transfer_ctx, amount)
+    }
+
+    pub fn get_quest_info(ctx: Context<GetQuestInfo>) -> Result<Quest> {
+        Ok((*ctx.accounts.quest).clone())
+    }
+
+    pub fn get_quest_context<'info>(
+        ctx: Context<'_, '_, 'info, 'info, GetQuestContext<'info>>,
+    ) -> Result<QuestContext> {
+        // The reward record is optional and passed as the first remaining account
+        let reward_claimed = match ctx.remaining_accounts.first() {
+            Some(reward_claimed_info) => {
+                let reward_claimed: Account<'info, RewardClaimed> =
+                    Account::try_from(reward_claimed_info)?;
+                require_keys_eq!(
+                    reward_claimed.quest,
+                    ctx.accounts.quest.key(),
+                    CustomError::InvalidRewardClaimedAccount
+                );
+                Some((*reward_claimed).clone())
+            }
+            None => None,
+        };
+
+        let global_state = &ctx.accounts.global_state;
+        Ok(QuestContext {
+            global: GlobalSummary {
+                owner: global_state.owner,
+                paused: global_state.paused,
+                claims_paused: global_state.claims_paused,
+                quest_count: global_state.quest_count,
+                global_min_reward: global_state.global_min_reward,
+                large_reward_threshold: global_state.large_reward_threshold,
+                required_approvals: global_state.required_approvals,
+                mint_supported: global_state
+                    .supported_token_mints
+                    .contains(&ctx.accounts.quest.token_mint),
+            },
+            quest: (*ctx.accounts.quest).clone(),
+            reward_claimed,
+        })
+    }
+
+    pub fn get_quest_report(ctx: Context<GetQuestInfo>) -> Result<QuestReport> {
+        let quest = &ctx.accounts.quest;
+        let remaining = quest
+            .amount
+            .checked_sub(quest.total_reward_distributed)
+            .ok_or(CustomError::InvariantViolation)?;
+        let average_payout_interval = (quest.completed_at != 0 && quest.total_winners > 0)
+            .then(|| (quest.completed_at - quest.first_reward_at) / i64::from(quest.total_winners));
+        Ok(QuestReport {
+            amount: quest.amount,
+            distributed: quest.total_reward_distributed,
+            remaining,
+            winner_count: quest.total_winners,
+            max_winners: quest.max_winners,
+            average_payout_interval,
+        })
+    }
+
+    pub fn preview_batch(ctx: Context<PreviewBatch>, amounts: Vec<u64>) -> Result<BatchPreview> {
+        let quest = &ctx.accounts.quest;
+        // Amounts send_reward_batch would reject outright fail the preview too
+        let amounts = validate_batch_amounts(&ctx.accounts.global_state, quest, &amounts)?;
+        let total = amounts
             .iter()
-            .position(|x| *x == token_mint)
-            .ok_or(CustomError::TokenNotFound)?;
+            .try_fold(0u64, |acc, amount| acc.checked_add(*amount))
+            .ok_or(CustomError::ArithmeticOverflow)?;
+        let distributed_after = quest
+            .total_reward_distributed
+            .checked_add(total)
+            .ok_or(CustomError::ArithmeticOverflow)?;
+        let winners_after = u32::try_from(amounts.len())
+            .ok()
+            .and_then(|count| quest.total_winners.checked_add(count))
+            .ok_or(CustomError::WinnerCountOverflow)?;
 
-        global_state.supported_token_mints.remove(position);
-        Ok(())
+        // Counts the reserve, which stays locked until the deadline
+        let fits_escrow = ensure_distributable(quest, total).is_ok()
+            && total <= ctx.accounts.escrow_account.amount;
+        let fits_slots = winners_after <= quest.max_winners;
+        Ok(BatchPreview {
+            total,
+            fits_escrow,
+            fits_slots,
+            would_complete: fits_escrow
+                && fits_slots
+                && (distributed_after == quest.amount || winners_after == quest.max_winners),
+        })
     }
 
-    pub fn pause(ctx: Context<PauseContract>) -> Result<()> {
-        require!(
-            ctx.accounts.owner.key() == ctx.accounts.global_state.owner,
-            CustomError::UnauthorizedPauseAction
-        );
+    pub fn get_escrow_shortfall(ctx: Context<GetEscrowShortfall>) -> Result<i64> {
+        // Negative when the escrow holds less than the quest still owes
+        let quest = &ctx.accounts.quest;
+        let owed = quest
+            .amount
+            .checked_sub(quest.total_reward_distributed)
+            .ok_or(CustomError::InvariantViolation)?;
+        let held = i64::try_from(ctx.accounts.escrow_account.amount)
+            .map_err(|_| CustomError::ArithmeticOverflow)?;
+        let owed = i64::try_from(owed).map_err(|_| CustomError::ArithmeticOverflow)?;
+        Ok(held
+            .checked_sub(owed)
+            .ok_or(CustomError::ArithmeticOverflow)?)
+    }
 
-        let global_state = &mut ctx.accounts.global_state;
-        require!(!global_state.paused, CustomError::AlreadyPaused);
+    pub fn quest_health_check(ctx: Context<QuestHealthCheck>) -> Result<HealthReport> {
+        let quest = &ctx.accounts.quest;
+        let escrow = &ctx.accounts.escrow_account;
+        let (global_state, _) = Pubkey::find_program_address(&[GLOBAL_STATE_SEED], ctx.program_id);
 
-        global_state.paused = true;
-        Ok(())
+        let distributed_within_amount = quest.total_reward_distributed <= quest.amount;
+        let winners_within_max = quest.total_winners <= quest.max_winners;
+        let escrow_solvent =
+            escrow.amount >= quest.amount.saturating_sub(quest.total_reward_distributed);
+        let escrow_linked = escrow.key() == quest.escrow_account
+            && escrow.mint == quest.token_mint
+            && escrow.owner == global_state;
+        Ok(HealthReport {
+            distributed_within_amount,
+            winners_within_max,
+            escrow_solvent,
+            escrow_linked,
+            healthy: distributed_within_amount
+                && winners_within_max
+                && escrow_solvent
+                && escrow_linked,
+        })
     }
 
-    pub fn unpause(ctx: Context<PauseContract>) -> Result<()> {
-        require!(
-            ctx.accounts.owner.key() == ctx.accounts.global_state.owner,
-            CustomError::UnauthorizedPauseAction
-        );
+    pub fn is_remaining_claimable(ctx: Context<IsRemainingClaimable>) -> Result<bool> {
+        // Mirrors the preconditions of claim_remaining_reward, minus the
+        // signer check
+        let quest = &ctx.accounts.quest;
+        // cancel_quest already refunded the escrow, and a completed quest has
+        // nothing left; neither can be completed by a claim
+        if matches!(
+            QuestStatus::of(quest),
+            QuestStatus::Cancelled | QuestStatus::Completed
+        ) {
+            return Ok(false);
+        }
 
-        let global_state = &mut ctx.accounts.global_state;
-        require!(global_state.paused, CustomError::AlreadyUnpaused);
+        let remaining_amount = quest.amount.saturating_sub(quest.total_reward_distributed);
+        let now = Clock::get()?.unix_timestamp;
+        Ok(!ctx.accounts.global_state.paused
+            && !ctx.accounts.global_state.claims_paused
+            && !quest.is_active
+            && !quest.paused
+            && !quest.owner_frozen
+            && now >= quest.deadline.saturating_add(REMAINING_CLAIM_DELAY_SECONDS)
+            && remaining_amount > 0
+            && ctx.accounts.escrow_account.amount >= remaining_amount)
+    }
 
-        global_state.paused = false;
-        Ok(())
+    pub fn get_winner_reward(ctx: Context<GetWinnerReward>) -> Result<WinnerReward> {
+        let reward_claimed = &ctx.accounts.reward_claimed;
+        Ok(WinnerReward {
+            winner: reward_claimed.winner,
+            quest: reward_claimed.quest,
+            reward_amount: reward_claimed.reward_amount,
+            claimed: reward_claimed.claimed,
+            pda: reward_claimed.key(),
+            bump: reward_claimed.bump,
+            reason: reward_claimed.reason,
+        })
     }
 
-    pub fn set_owner(ctx: Context<SetOwner>, new_owner: Pubkey) -> Result<()> {
-        // Only current owner can rotate ownership
+    pub fn get_time_remaining(ctx: Context<GetQuestInfo>) -> Result<i64> {
+        let now = Clock::get()?.unix_timestamp;
+        Ok(ctx.accounts.quest.deadline.saturating_sub(now).max(0))
+    }
+
+    pub fn check_winner_claimed_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CheckWinnerClaimed<'info>>,
+        quests: Vec<Pubkey>,
+    ) -> Result<Vec<bool>> {
+        // One RewardClaimed PDA per quest, in the same order
         require!(
-            ctx.accounts.current_owner.key() == ctx.accounts.global_state.owner,
-            CustomError::UnauthorizedRewardAction
+            ctx.remaining_accounts.len() == quests.len(),
+            CustomError::InvalidBatch
         );
 
-        let global_state = &mut ctx.accounts.global_state;
-        global_state.owner = new_owner;
-        Ok(())
+        let winner = ctx.accounts.winner.key();
+        quests
+            .iter()
+            .zip(ctx.remaining_accounts)
+            .map(|(quest, reward_claimed_info)| {
+                let (expected_reward_claimed, _) = Pubkey::find_program_address(
+                    &[b"reward_claimed", quest.as_ref(), winner.as_ref()],
+                    ctx.program_id,
+                );
+                require_keys_eq!(
+                    reward_claimed_info.key(),
+                    expected_reward_claimed,
+                    CustomError::InvalidRewardClaimedAccount
+                );
+                // No record, or a closed one, means nothing was claimed
+                if reward_claimed_info.owner != ctx.program_id {
+                    return Ok(false);
+                }
+                let reward_claimed: Account<'info, RewardClaimed> =
+                    Account::try_from(reward_claimed_info)?;
+                Ok(reward_claimed.claimed)
+            })
+            .collect()
     }
 
-    pub fn send_reward(ctx: Context<SendReward>, reward_amount: u64) -> Result<()> {
+    pub fn get_escrow_address(ctx: Context<GetQuestInfo>) -> Result<Pubkey> {
+        let (escrow, _) = Pubkey::find_program_address(
+            &[ESCROW_SEED, ctx.accounts.quest.key().as_ref()],
+            ctx.program_id,
+        );
+        Ok(escrow)
+    }
+
+    pub fn estimate_quest_rent(
+        _ctx: Context<EstimateQuestRent>,
+        include_reward_claimed: bool,
+    ) -> Result<u64> {
+        let rent = Rent::get()?;
+        let mut total = rent
+            .minimum_balance(QUEST_SPACE)
+            .checked_add(rent.minimum_balance(TokenAccount::LEN))
+            .and_then(|total| total.checked_add(rent.minimum_balance(QUEST_ID_REGISTRY_SPACE)))
+            .ok_or(CustomError::ArithmeticOverflow)?;
+        if include_reward_claimed {
+            total = total
+                .checked_add(rent.minimum_balance(REWARD_CLAIMED_SPACE))
+                .ok_or(CustomError::ArithmeticOverflow)?;
+        }
+        Ok(total)
+    }
+
+    pub fn to_base_units(ctx: Context<ToBaseUnits>, whole_amount: u64) -> Result<u64> {
+        let scale = 10u64
+            .checked_pow(u32::from(ctx.accounts.token_mint.decimals))
+            .ok_or(CustomError::ArithmeticOverflow)?;
+        Ok(whole_amount
+            .checked_mul(scale)
+            .ok_or(CustomError::ArithmeticOverflow)?)
+    }
+
+    pub fn get_all_quests(_ctx: Context<GetAllQuests>) -> Result<Vec<String>> {
+        // NOTE: quests changed to Vec<Pubkey> for consistency.
+        // This function is deprecated; prefer fetching quest accounts directly client-side.
+        Ok(Vec::new())
+    }
+
+    pub fn cancel_quest(ctx: Context<CancelQuest>) -> Result<()> {
         require!(
             !ctx.accounts.global_state.paused,
             CustomError::ContractPaused
         );
         require!(
-            ctx.accounts.owner.key() == ctx.accounts.global_state.owner,
-            CustomError::UnauthorizedRewardAction
+            !ctx.accounts.global_state.claims_paused,
+            CustomError::ClaimsPaused
         );
-
         let quest = &mut ctx.accounts.quest;
-        require!(quest.is_active, CustomError::QuestNotActive);
-        require!(
-            quest.total_reward_distributed + reward_amount <= quest.amount,
-            CustomError::InsufficientRewardBalance
-        );
-        require!(
-            quest.total_winners < quest.max_winners,
-            CustomError::MaxWinnersReached
-        );
+        // The refund drains the escrow, so it waits out pauses and freezes
+        // like any payout
+        require!(!quest.paused, CustomError::QuestPaused);
+        require!(!quest.owner_frozen, CustomError::QuestFrozen);
 
-        // Validate winner token account (ATA) exists and is correct
-        // This provides clear error messages for missing ATAs before attempting transfer
-        let winner_token = &ctx.accounts.winner_token_account;
-        require!(
-            winner_token.mint == quest.token_mint,
-            CustomError::MissingAssociatedTokenAccount
-        );
+        // Remaining funds were already returned; the quest is terminal
+        require!(!quest.finalized, CustomError::QuestFinalized);
+        require!(quest.is_active, CustomError::QuestNotActive);
         require!(
-            winner_token.owner == ctx.accounts.winner.key(),
-            CustomError::MissingAssociatedTokenAccount
+            quest.creator == ctx.accounts.creator.key(),
+            CustomError::UnauthorizedCancellation
         );
 
-        // Check if winner has already claimed reward
-        let reward_claimed_pda = &mut ctx.accounts.reward_claimed;
-        require!(!reward_claimed_pda.claimed, CustomError::AlreadyRewarded);
-
-        // Update quest state
-        quest.total_reward_distributed += reward_amount;
-        quest.total_winners += 1;
-
-        // Initialize reward claimed account
-        // Note: quest.id is String, but RewardClaimed.quest stores Pubkey for consistency
-        reward_claimed_pda.quest = ctx.accounts.quest.key(); // Using quest.key() (Pubkey) instead of quest.id (String)
-        reward_claimed_pda.winner = ctx.accounts.winner.key();
-        reward_claimed_pda.reward_amount = reward_amount;
-        reward_claimed_pda.claimed = true;
-
-        // Transfer reward tokens from escrow to winner
         let signer_seeds: &[&[&[u8]]] = &[&[GLOBAL_STATE_SEED, &[ctx.bumps.global_state]]];
+
+        // Transfer tokens back to creator
         let transfer_ctx = CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
             Transfer {
                 from: ctx.accounts.escrow_account.to_account_info(),
-                to: ctx.accounts.winner_token_account.to_account_info(),
+                to: ctx.accounts.creator_token_account.to_account_info(),
                 authority: ctx.accounts.global_state.to_account_info(),
             },
             signer_seeds,
         );
-        token::transfer(transfer_ctx, reward_amount)?;
+        token::transfer(transfer_ctx, ctx.accounts.escrow_account.amount)?;
 
-        Ok(())
+        transition(quest, QuestStatus::Cancelled)
     }
 
-    pub fn claim_remaining_reward(ctx: Context<ClaimRemainingReward>) -> Result<()> {
+    pub fn reactivate_quest(ctx: Context<ReactivateQuest>, amount: u64) -> Result<()> {
         require!(
             !ctx.accounts.global_state.paused,
             CustomError::ContractPaused
         );
 
         let quest = &mut ctx.accounts.quest;
-
-        // Only quest creator or admin can call this function
         require!(
-            quest.creator == ctx.accounts.claimer.key()
-                || ctx.accounts.claimer.key() == ctx.accounts.global_state.owner,
-            CustomError::UnauthorizedWithdrawal
+            quest.creator == ctx.accounts.creator.key(),
+            CustomError::UnauthorizedQuestUpdate
         );
-
-        // Quest must be inactive (ended)
-        require!(!quest.is_active, CustomError::QuestNotActive);
-
-        // Must wait 1 week after quest deadline (7 days = 604800 seconds)
-        let current_timestamp = Clock::get()?.unix_timestamp;
+        // Only a cancelled quest can come back; one that ran its course stays done
+        require!(quest.cancelled, CustomError::QuestNotCancelled);
+        require!(!quest.finalized, CustomError::QuestFinalized);
+        require!(!quest.records_closed, CustomError::RewardRecordsClosed);
         require!(
-            current_timestamp >= quest.deadline + 604800,
-            CustomError::WithdrawalTooEarly
+            ctx.accounts.escrow_account.amount == 0,
+            CustomError::EscrowNotEmpty
         );
+        require!(amount > 0, CustomError::InvalidAmount);
 
-        // Calculate remaining unclaimed amount
-        let remaining_amount = quest.amount - quest.total_reward_distributed;
-        require!(remaining_amount > 0, CustomError::NoTokensToWithdraw);
-
-        // Update the quest to prevent double claiming by setting amount to distributed amount
-        quest.amount = quest.total_reward_distributed;
+        // Re-fund on top of what was already paid out so the historical
+        // counters keep adding up
+        quest.amount = quest
+            .total_reward_distributed
+            .checked_add(amount)
+            .ok_or(CustomError::ArithmeticOverflow)?;
+        // A per-quest pause is the owner's to lift, so it carries over
+        transition(quest, QuestStatus::Active)?;
+        quest.reserve_amount = 0;
 
-        // Transfer remaining tokens to creator
-        let signer_seeds: &[&[&[u8]]] = &[&[GLOBAL_STATE_SEED, &[ctx.bumps.global_state]]];
-        let transfer_ctx = CpiContext::new_with_signer(
+        let transfer_ctx = CpiContext::new(
             ctx.accounts.token_program.to_account_info(),
             Transfer {
-                from: ctx.accounts.escrow_account.to_account_info(),
-                to: ctx.accounts.creator_token_account.to_account_info(),
-                authority: ctx.accounts.global_state.to_account_info(),
+                from: ctx.accounts.creator_token_account.to_account_info(),
+                to: ctx.accounts.escrow_account.to_account_info(),
+                authority: ctx.accounts.creator.to_account_info(),
             },
-            signer_seeds,
         );
-        token::transfer(transfer_ctx, remaining_amount)?;
+        token::transfer(transfer_ctx, amount)?;
 
         Ok(())
     }
-}
 
-#[error_code]
-pub enum CustomError {
-    #[msg("Contract is paused")]
-    ContractPaused,
-    #[msg("Unsupported token mint")]
-    UnsupportedTokenMint,
-    #[msg("Unauthorized cancellation")]
-    UnauthorizedCancellation,
-    #[msg("Quest is not active")]
-    QuestNotActive,
-    #[msg("Quest already cancelled")]
-    QuestAlreadyCancelled,
-    #[msg("Unauthorized status update")]
-    UnauthorizedStatusUpdate,
+    pub fn set_reserve_amount(ctx: Context<UpdateQuestConfig>, reserve_amount: u64) -> Result<()> {
+        let quest = &mut ctx.accounts.quest;
+        require!(
+            quest.creator == ctx.accounts.creator.key(),
+            CustomError::UnauthorizedQuestUpdate
+        );
+        require!(quest.is_active, CustomError::QuestNotActive);
+        require!(
+            reserve_amount <= quest.amount,
+            CustomError::InvalidReserveAmount
+        );
+
+        quest.reserve_amount = reserve_amount;
+        Ok(())
+    }
+
+    pub fn set_max_single_reward_bps(ctx: Context<UpdateQuestConfig>, bps: u16) -> Result<()> {
+        let quest = &mut ctx.accounts.quest;
+        require!(
+            quest.creator == ctx.accounts.creator.key(),
+            CustomError::UnauthorizedQuestUpdate
+        );
+        require!(
+            u64::from(bps) <= BPS_DENOMINATOR,
+            CustomError::InvalidConfigValue
+        );
+
+        quest.max_single_reward_bps = bps;
+        Ok(())
+    }
+
+    pub fn set_quest_bonus(
+        ctx: Context<UpdateQuestConfig>,
+        bonus_bps: u16,
+        bonus_until: i64,
+    ) -> Result<()> {
+        let quest = &mut ctx.accounts.quest;
+        require!(
+            quest.creator == ctx.accounts.creator.key(),
+            CustomError::UnauthorizedQuestUpdate
+        );
+        require!(
+            u64::from(bonus_bps) <= BPS_DENOMINATOR,
+            CustomError::InvalidConfigValue
+        );
+
+        quest.bonus_bps = bonus_bps;
+        quest.bonus_until = bonus_until;
+        Ok(())
+    }
+
+    pub fn set_leftover_policy(
+        ctx: Context<UpdateQuestConfig>,
+        leftover_policy: LeftoverPolicy,
+    ) -> Result<()> {
+        let quest = &mut ctx.accounts.quest;
+        require!(
+            quest.creator == ctx.accounts.creator.key(),
+            CustomError::UnauthorizedQuestUpdate
+        );
+        require!(!quest.finalized, CustomError::QuestFinalized);
+
+        quest.leftover_policy = leftover_policy;
+        Ok(())
+    }
+
+    pub fn reconcile_quest(ctx: Context<ReconcileQuest>) -> Result<()> {
+        let quest = &mut ctx.accounts.quest;
+        require!(
+            quest.creator == ctx.accounts.creator.key(),
+            CustomError::UnauthorizedQuestUpdate
+        );
+        require!(quest.is_active, CustomError::QuestNotActive);
+
+        // Whatever sits in escrow is undistributed, so the amount can never
+        // drop below what has already been paid out
+        quest.amount = quest
+            .total_reward_distributed
+            .checked_add(ctx.accounts.escrow_account.amount)
+            .ok_or(CustomError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    pub fn update_quest_status(ctx: Context<UpdateQuestStatus>, is_active: bool) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.global_state.owner,
+            CustomError::UnauthorizedStatusUpdate
+        );
+
+        let quest = &mut ctx.accounts.quest;
+        // The owner toggle only moves live quests; cancelled ones come back
+        // through reactivate_quest
+        require!(
+            !matches!(
+                QuestStatus::of(quest),
+                QuestStatus::Cancelled | QuestStatus::Completed
+            ),
+            CustomError::InvalidStateTransition
+        );
+        if quest.is_active == is_active {
+            return Ok(());
+        }
+        let status = if is_active {
+            QuestStatus::Active
+        } else {
+            QuestStatus::Inactive
+        };
+        transition(quest, status)?;
+        record_audit_entry(
+            &ctx.accounts.global_state,
+            ctx.accounts.audit_log.as_mut(),
+            ACTION_SET_QUEST_STATUS,
+            ctx.accounts.owner.key(),
+        )
+    }
+
+    pub fn add_supported_token(ctx: Context<ModifyToken>) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.global_state.owner,
+            CustomError::UnauthorizedTokenModification
+        );
+
+        let global_state = &mut ctx.accounts.global_state;
+        let token_mint = ctx.accounts.token_mint.key();
+
+        require!(
+            !global_state.supported_token_mints.contains(&token_mint),
+            CustomError::TokenAlreadySupported
+        );
+
+        global_state.supported_token_mints.push(token_mint);
+        record_audit_entry(
+            &ctx.accounts.global_state,
+            ctx.accounts.audit_log.as_mut(),
+            ACTION_ADD_SUPPORTED_TOKEN,
+            ctx.accounts.owner.key(),
+        )
+    }
+
+    pub fn remove_supported_token(ctx: Context<ModifyToken>) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.global_state.owner,
+            CustomError::UnauthorizedTokenModification
+        );
+
+        let global_state = &mut ctx.accounts.global_state;
+        let token_mint = ctx.accounts.token_mint.key();
+
+        let position = global_state
+            .supported_token_mints
+            .iter()
+            .position(|x| *x == token_mint)
+            .ok_or(CustomError::TokenNotFound)?;
+
+        global_state.supported_token_mints.remove(position);
+        record_audit_entry(
+            &ctx.accounts.global_state,
+            ctx.accounts.audit_log.as_mut(),
+            ACTION_REMOVE_SUPPORTED_TOKEN,
+            ctx.accounts.owner.key(),
+        )
+    }
+
+    pub fn remove_supported_tokens(ctx: Context<ModifyTokens>, mints: Vec<Pubkey>) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.global_state.owner,
+            CustomError::UnauthorizedTokenModification
+        );
+        require!(!mints.is_empty(), CustomError::TokenNotFound);
+
+        // All-or-nothing: any unknown mint reverts the whole call
+        let global_state = &mut ctx.accounts.global_state;
+        for mint in &mints {
+            let position = global_state
+                .supported_token_mints
+                .iter()
+                .position(|x| x == mint)
+                .ok_or(CustomError::TokenNotFound)?;
+            global_state.supported_token_mints.remove(position);
+        }
+
+        record_audit_entry(
+            &ctx.accounts.global_state,
+            ctx.accounts.audit_log.as_mut(),
+            ACTION_REMOVE_SUPPORTED_TOKEN,
+            ctx.accounts.owner.key(),
+        )
+    }
+
+    pub fn set_supported_tokens(ctx: Context<ModifyTokens>, mints: Vec<Pubkey>) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.global_state.owner,
+            CustomError::UnauthorizedTokenModification
+        );
+
+        let mut supported_token_mints: Vec<Pubkey> = Vec::with_capacity(mints.len());
+        for mint in mints {
+            require!(mint != Pubkey::default(), CustomError::InvalidTokenMint);
+            if !supported_token_mints.contains(&mint) {
+                supported_token_mints.push(mint);
+            }
+        }
+        require!(
+            supported_token_mints.len() <= MAX_SUPPORTED_TOKEN_MINTS,
+            CustomError::TooManyTokenMints
+        );
+
+        ctx.accounts.global_state.supported_token_mints = supported_token_mints;
+        record_audit_entry(
+            &ctx.accounts.global_state,
+            ctx.accounts.audit_log.as_mut(),
+            ACTION_SET_SUPPORTED_TOKENS,
+            ctx.accounts.owner.key(),
+        )
+    }
+
+    pub fn pause(ctx: Context<PauseContract>) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.global_state.owner,
+            CustomError::UnauthorizedPauseAction
+        );
+
+        let global_state = &mut ctx.accounts.global_state;
+        require!(!global_state.paused, CustomError::AlreadyPaused);
+
+        global_state.paused = true;
+        record_audit_entry(
+            &ctx.accounts.global_state,
+            ctx.accounts.audit_log.as_mut(),
+            ACTION_PAUSE,
+            ctx.accounts.owner.key(),
+        )
+    }
+
+    pub fn unpause(ctx: Context<PauseContract>) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.global_state.owner,
+            CustomError::UnauthorizedPauseAction
+        );
+
+        let global_state = &mut ctx.accounts.global_state;
+        require!(global_state.paused, CustomError::AlreadyUnpaused);
+
+        global_state.paused = false;
+        record_audit_entry(
+            &ctx.accounts.global_state,
+            ctx.accounts.audit_log.as_mut(),
+            ACTION_UNPAUSE,
+            ctx.accounts.owner.key(),
+        )
+    }
+
+    pub fn pause_claims(ctx: Context<PauseContract>) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.global_state.owner,
+            CustomError::UnauthorizedPauseAction
+        );
+
+        let global_state = &mut ctx.accounts.global_state;
+        require!(!global_state.claims_paused, CustomError::AlreadyPaused);
+
+        global_state.claims_paused = true;
+        record_audit_entry(
+            &ctx.accounts.global_state,
+            ctx.accounts.audit_log.as_mut(),
+            ACTION_PAUSE_CLAIMS,
+            ctx.accounts.owner.key(),
+        )
+    }
+
+    pub fn unpause_claims(ctx: Context<PauseContract>) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.global_state.owner,
+            CustomError::UnauthorizedPauseAction
+        );
+
+        let global_state = &mut ctx.accounts.global_state;
+        require!(global_state.claims_paused, CustomError::AlreadyUnpaused);
+
+        global_state.claims_paused = false;
+        record_audit_entry(
+            &ctx.accounts.global_state,
+            ctx.accounts.audit_log.as_mut(),
+            ACTION_UNPAUSE_CLAIMS,
+            ctx.accounts.owner.key(),
+        )
+    }
+
+    pub fn pause_quests<'info>(
+        ctx: Context<'_, '_, 'info, 'info, PauseContract<'info>>,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.global_state.owner,
+            CustomError::UnauthorizedPauseAction
+        );
+        set_quests_paused(ctx.remaining_accounts, true)?;
+        record_audit_entry(
+            &ctx.accounts.global_state,
+            ctx.accounts.audit_log.as_mut(),
+            ACTION_PAUSE_QUESTS,
+            ctx.accounts.owner.key(),
+        )
+    }
+
+    pub fn unpause_quests<'info>(
+        ctx: Context<'_, '_, 'info, 'info, PauseContract<'info>>,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.global_state.owner,
+            CustomError::UnauthorizedPauseAction
+        );
+        set_quests_paused(ctx.remaining_accounts, false)?;
+        record_audit_entry(
+            &ctx.accounts.global_state,
+            ctx.accounts.audit_log.as_mut(),
+            ACTION_UNPAUSE_QUESTS,
+            ctx.accounts.owner.key(),
+        )
+    }
+
+    pub fn owner_freeze_quest(ctx: Context<OwnerModerateQuest>) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.global_state.owner,
+            CustomError::UnauthorizedPauseAction
+        );
+        ctx.accounts.quest.owner_frozen = true;
+        record_audit_entry(
+            &ctx.accounts.global_state,
+            ctx.accounts.audit_log.as_mut(),
+            ACTION_FREEZE_QUEST,
+            ctx.accounts.owner.key(),
+        )
+    }
+
+    pub fn owner_unfreeze_quest(ctx: Context<OwnerModerateQuest>) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.global_state.owner,
+            CustomError::UnauthorizedPauseAction
+        );
+        ctx.accounts.quest.owner_frozen = false;
+        record_audit_entry(
+            &ctx.accounts.global_state,
+            ctx.accounts.audit_log.as_mut(),
+            ACTION_UNFREEZE_QUEST,
+            ctx.accounts.owner.key(),
+        )
+    }
+
+    pub fn owner_set_max_winners(ctx: Context<OwnerModerateQuest>, new_max: u32) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.global_state.owner,
+            CustomError::UnauthorizedQuestUpdate
+        );
+
+        let quest = &mut ctx.accounts.quest;
+        require!(
+            new_max >= quest.total_winners,
+            CustomError::MaxWinnersBelowTotal
+        );
+
+        let old_max_winners = quest.max_winners;
+        quest.max_winners = new_max;
+
+        emit!(MaxWinnersOverridden {
+            quest: quest.key(),
+            owner: ctx.accounts.owner.key(),
+            old_max_winners,
+            new_max_winners: new_max,
+        });
+        record_audit_entry(
+            &ctx.accounts.global_state,
+            ctx.accounts.audit_log.as_mut(),
+            ACTION_SET_MAX_WINNERS,
+            ctx.accounts.owner.key(),
+        )
+    }
+
+    pub fn recount_winners<'info>(
+        ctx: Context<'_, '_, 'info, 'info, OwnerModerateQuest<'info>>,
+    ) -> Result<u32> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.global_state.owner,
+            CustomError::UnauthorizedQuestUpdate
+        );
+        require!(
+            !ctx.remaining_accounts.is_empty(),
+            CustomError::InvalidRewardClaimedAccount
+        );
+
+        // The caller must pass every record of the quest; a record listed twice
+        // or belonging to another quest would inflate or skew the count
+        let quest_key = ctx.accounts.quest.key();
+        let mut seen: Vec<Pubkey> = Vec::with_capacity(ctx.remaining_accounts.len());
+        let mut verified: u32 = 0;
+        let mut recorded_amount: u64 = 0;
+        for reward_claimed_info in ctx.remaining_accounts {
+            require!(
+                !seen.contains(reward_claimed_info.key),
+                CustomError::InvalidRewardClaimedAccount
+            );
+            seen.push(reward_claimed_info.key());
+
+            let reward_claimed: Account<'info, RewardClaimed> =
+                Account::try_from(reward_claimed_info)?;
+            require_keys_eq!(
+                reward_claimed.quest,
+                quest_key,
+                CustomError::InvalidRewardClaimedAccount
+            );
+            if reward_claimed.claimed {
+                verified = verified
+                    .checked_add(1)
+                    .ok_or(CustomError::WinnerCountOverflow)?;
+                recorded_amount = recorded_amount
+                    .checked_add(reward_claimed.reward_amount)
+                    .ok_or(CustomError::ArithmeticOverflow)?;
+            }
+        }
+
+        // Every payout leaves a record, so only the full set adds up to what the
+        // quest has paid out; a partial set would lower the count and reopen
+        // winner slots. Merged quests keep the source's totals while its records
+        // stay keyed to the source, so they are rejected here as well.
+        let quest = &mut ctx.accounts.quest;
+        require!(
+            recorded_amount == quest.total_reward_distributed,
+            CustomError::IncompleteRewardRecords
+        );
+        // Zero-amount payouts add nothing to the sum, so a set missing them still
+        // balances; the recount may raise the counter but never lower it
+        require!(
+            verified >= quest.total_winners,
+            CustomError::IncompleteRewardRecords
+        );
+        if quest.total_winners != verified {
+            msg!(
+                "Repaired total_winners: {} -> {}",
+                quest.total_winners,
+                verified
+            );
+            quest.total_winners = verified;
+        }
+        record_audit_entry(
+            &ctx.accounts.global_state,
+            ctx.accounts.audit_log.as_mut(),
+            ACTION_RECOUNT,
+            ctx.accounts.owner.key(),
+        )?;
+        Ok(verified)
+    }
+
+    pub fn recount_quests<'info>(
+        ctx: Context<'_, '_, 'info, 'info, UpdateConfig<'info>>,
+        reset: bool,
+    ) -> Result<u32> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.global_state.owner,
+            CustomError::UnauthorizedConfigUpdate
+        );
+        require!(
+            !ctx.remaining_accounts.is_empty(),
+            CustomError::NoQuestsProvided
+        );
+
+        // Quests are passed in chunks that fit a transaction; the first chunk
+        // resets the count and each quest must appear in exactly one chunk
+        let mut seen: Vec<Pubkey> = Vec::with_capacity(ctx.remaining_accounts.len());
+        for quest_info in ctx.remaining_accounts {
+            require!(
+                !seen.contains(quest_info.key),
+                CustomError::DuplicateQuestAccount
+            );
+            seen.push(quest_info.key());
+            // Checks program ownership and the Quest discriminator
+            Account::<'info, Quest>::try_from(quest_info)?;
+        }
+        let chunk_count = u32::try_from(seen.len()).map_err(|_| CustomError::ArithmeticOverflow)?;
+
+        let global_state = &mut ctx.accounts.global_state;
+        let base = if reset { 0 } else { global_state.quest_count };
+        let quest_count = base
+            .checked_add(chunk_count)
+            .ok_or(CustomError::ArithmeticOverflow)?;
+        msg!(
+            "Recounted quest_count: {} -> {}",
+            global_state.quest_count,
+            quest_count
+        );
+        global_state.quest_count = quest_count;
+        record_audit_entry(
+            &ctx.accounts.global_state,
+            ctx.accounts.audit_log.as_mut(),
+            ACTION_RECOUNT,
+            ctx.accounts.owner.key(),
+        )?;
+        Ok(quest_count)
+    }
+
+    pub fn set_min_quest_duration(ctx: Context<UpdateConfig>, seconds: i64) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.global_state.owner,
+            CustomError::UnauthorizedConfigUpdate
+        );
+        require!(seconds >= 0, CustomError::InvalidConfigValue);
+
+        ctx.accounts.global_state.min_quest_duration_seconds = seconds;
+        record_audit_entry(
+            &ctx.accounts.global_state,
+            ctx.accounts.audit_log.as_mut(),
+            ACTION_UPDATE_CONFIG,
+            ctx.accounts.owner.key(),
+        )
+    }
+
+    pub fn set_default_quest_duration(ctx: Context<UpdateConfig>, seconds: i64) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.global_state.owner,
+            CustomError::UnauthorizedConfigUpdate
+        );
+        require!(seconds >= 0, CustomError::InvalidConfigValue);
+
+        ctx.accounts.global_state.default_quest_duration_seconds = seconds;
+        record_audit_entry(
+            &ctx.accounts.global_state,
+            ctx.accounts.audit_log.as_mut(),
+            ACTION_UPDATE_CONFIG,
+            ctx.accounts.owner.key(),
+        )
+    }
+
+    pub fn set_global_min_reward(ctx: Context<UpdateConfig>, min_reward: u64) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.global_state.owner,
+            CustomError::UnauthorizedConfigUpdate
+        );
+
+        ctx.accounts.global_state.global_min_reward = min_reward;
+        record_audit_entry(
+            &ctx.accounts.global_state,
+            ctx.accounts.audit_log.as_mut(),
+            ACTION_UPDATE_CONFIG,
+            ctx.accounts.owner.key(),
+        )
+    }
+
+    pub fn set_max_quest_id_length(ctx: Context<UpdateConfig>, max_length: u8) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.global_state.owner,
+            CustomError::UnauthorizedConfigUpdate
+        );
+        // Quest accounts are sized for the hard cap, so only tighter limits fit
+        require!(
+            max_length > 0 && usize::from(max_length) <= MAX_QUEST_ID_LENGTH,
+            CustomError::InvalidConfigValue
+        );
+
+        ctx.accounts.global_state.max_quest_id_length = max_length;
+        record_audit_entry(
+            &ctx.accounts.global_state,
+            ctx.accounts.audit_log.as_mut(),
+            ACTION_UPDATE_CONFIG,
+            ctx.accounts.owner.key(),
+        )
+    }
+
+    pub fn set_enforce_min_reward_feasibility(
+        ctx: Context<UpdateConfig>,
+        enabled: bool,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.global_state.owner,
+            CustomError::UnauthorizedConfigUpdate
+        );
+
+        ctx.accounts.global_state.enforce_min_reward_feasibility = enabled;
+        record_audit_entry(
+            &ctx.accounts.global_state,
+            ctx.accounts.audit_log.as_mut(),
+            ACTION_UPDATE_CONFIG,
+            ctx.accounts.owner.key(),
+        )
+    }
+
+    pub fn set_large_reward_approval(
+        ctx: Context<UpdateConfig>,
+        threshold: u64,
+        approvers: Vec<Pubkey>,
+        required_approvals: u8,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.global_state.owner,
+            CustomError::UnauthorizedConfigUpdate
+        );
+        require!(
+            approvers.len() <= MAX_APPROVERS,
+            CustomError::InvalidConfigValue
+        );
+        for (i, approver) in approvers.iter().enumerate() {
+            require!(
+                !approvers[..i].contains(approver),
+                CustomError::InvalidConfigValue
+            );
+        }
+        // A zero threshold disables the approval flow entirely
+        if threshold > 0 {
+            require!(
+                required_approvals > 0 && usize::from(required_approvals) <= approvers.len(),
+                CustomError::InvalidConfigValue
+            );
+        }
+
+        let global_state = &mut ctx.accounts.global_state;
+        global_state.large_reward_threshold = threshold;
+        global_state.approvers = approvers;
+        global_state.required_approvals = required_approvals;
+        record_audit_entry(
+            &ctx.accounts.global_state,
+            ctx.accounts.audit_log.as_mut(),
+            ACTION_SET_LARGE_REWARD_APPROVAL,
+            ctx.accounts.owner.key(),
+        )
+    }
+
+    pub fn propose_large_reward(ctx: Context<ProposeLargeReward>, amount: u64) -> Result<()> {
+        let approver = ctx.accounts.approver.key();
+        require!(
+            ctx.accounts.global_state.approvers.contains(&approver),
+            CustomError::UnauthorizedApprover
+        );
+        // Smaller payouts never consult an approval, so one would only sit
+        // unused at the (quest, winner) address
+        let threshold = ctx.accounts.global_state.large_reward_threshold;
+        require!(
+            threshold != 0 && amount > threshold,
+            CustomError::BelowApprovalThreshold
+        );
+        require!(ctx.accounts.quest.is_active, CustomError::QuestNotActive);
+
+        let reward_approval = &mut ctx.accounts.reward_approval;
+        reward_approval.quest = ctx.accounts.quest.key();
+        reward_approval.winner = ctx.accounts.winner.key();
+        reward_approval.amount = amount;
+        reward_approval.proposer = approver;
+        reward_approval.approvals = vec![approver];
+        Ok(())
+    }
+
+    pub fn approve_large_reward(ctx: Context<ApproveLargeReward>) -> Result<()> {
+        let approver = ctx.accounts.approver.key();
+        require!(
+            ctx.accounts.global_state.approvers.contains(&approver),
+            CustomError::UnauthorizedApprover
+        );
+
+        let reward_approval = &mut ctx.accounts.reward_approval;
+        require!(
+            !reward_approval.approvals.contains(&approver),
+            CustomError::AlreadyApproved
+        );
+        reward_approval.approvals.push(approver);
+        Ok(())
+    }
+
+    /// Withdraws a proposal so the (quest, winner) pair can be proposed again,
+    /// e.g. with a corrected amount.
+    pub fn cancel_large_reward(ctx: Context<CancelLargeReward>) -> Result<()> {
+        let canceller = ctx.accounts.canceller.key();
+        let global_state = &ctx.accounts.global_state;
+        require!(
+            canceller == global_state.owner || global_state.approvers.contains(&canceller),
+            CustomError::UnauthorizedApprover
+        );
+        if canceller == global_state.owner {
+            record_audit_entry(
+                &ctx.accounts.global_state,
+                ctx.accounts.audit_log.as_mut(),
+                ACTION_CANCEL_LARGE_REWARD,
+                canceller,
+            )?;
+        }
+        Ok(())
+    }
+
+    pub fn set_enforce_supported_mint_on_payout(
+        ctx: Context<UpdateConfig>,
+        enabled: bool,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.global_state.owner,
+            CustomError::UnauthorizedConfigUpdate
+        );
+
+        ctx.accounts.global_state.enforce_supported_mint_on_payout = enabled;
+        record_audit_entry(
+            &ctx.accounts.global_state,
+            ctx.accounts.audit_log.as_mut(),
+            ACTION_UPDATE_CONFIG,
+            ctx.accounts.owner.key(),
+        )
+    }
+
+    pub fn add_blocked_address(ctx: Context<AddBlockedAddress>, address: Pubkey) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.global_state.owner,
+            CustomError::UnauthorizedConfigUpdate
+        );
+
+        let global_state = &mut ctx.accounts.global_state;
+        require!(
+            !global_state.blocklist.contains(&address),
+            CustomError::AddressAlreadyBlocked
+        );
+        require!(
+            global_state.blocklist.len() < MAX_BLOCKLIST_SIZE,
+            CustomError::BlocklistFull
+        );
+
+        global_state.blocklist.push(address);
+        record_audit_entry(
+            &ctx.accounts.global_state,
+            ctx.accounts.audit_log.as_mut(),
+            ACTION_BLOCK_ADDRESS,
+            ctx.accounts.owner.key(),
+        )
+    }
+
+    pub fn remove_blocked_address(
+        ctx: Context<RemoveBlockedAddress>,
+        address: Pubkey,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.global_state.owner,
+            CustomError::UnauthorizedConfigUpdate
+        );
+
+        let global_state = &mut ctx.accounts.global_state;
+        let position = global_state
+            .blocklist
+            .iter()
+            .position(|x| *x == address)
+            .ok_or(CustomError::AddressNotBlocked)?;
+
+        global_state.blocklist.remove(position);
+        record_audit_entry(
+            &ctx.accounts.global_state,
+            ctx.accounts.audit_log.as_mut(),
+            ACTION_UNBLOCK_ADDRESS,
+            ctx.accounts.owner.key(),
+        )
+    }
+
+    pub fn check_authorization(ctx: Context<CheckAuthorization>, action: u8) -> Result<bool> {
+        require!(
+            matches!(
+                action,
+                ACTION_PAUSE
+                    | ACTION_UNPAUSE
+                    | ACTION_SET_OWNER
+                    | ACTION_SEND_REWARD
+                    | ACTION_ADD_SUPPORTED_TOKEN
+                    | ACTION_REMOVE_SUPPORTED_TOKEN
+                    | ACTION_SET_SUPPORTED_TOKENS
+                    | ACTION_PAUSE_CLAIMS
+                    | ACTION_UNPAUSE_CLAIMS
+                    | ACTION_SET_QUEST_STATUS
+                    | ACTION_PAUSE_QUESTS
+                    | ACTION_UNPAUSE_QUESTS
+                    | ACTION_FREEZE_QUEST
+                    | ACTION_UNFREEZE_QUEST
+                    | ACTION_SET_MAX_WINNERS
+                    | ACTION_RECOUNT
+                    | ACTION_UPDATE_CONFIG
+                    | ACTION_SET_LARGE_REWARD_APPROVAL
+                    | ACTION_BLOCK_ADDRESS
+                    | ACTION_UNBLOCK_ADDRESS
+                    | ACTION_CLOSE_REWARD_RECORDS
+                    | ACTION_MIGRATE
+                    | ACTION_CLOSE_REWARD_CLAIMED
+                    | ACTION_CLAIM_REMAINING
+                    | ACTION_CANCEL_LARGE_REWARD
+                    | ACTION_INITIALIZE_AUDIT_LOG
+            ),
+            CustomError::UnknownAction
+        );
+
+        // Every privileged action is currently owner-only
+        Ok(ctx.accounts.signer.key() == ctx.accounts.global_state.owner)
+    }
+
+    pub fn get_owner_history(ctx: Context<GetConfig>) -> Result<Vec<Pubkey>> {
+        Ok(ctx.accounts.global_state.owner_history.clone())
+    }
+
+    pub fn get_pause_status(ctx: Context<GetConfig>) -> Result<PauseStatus> {
+        let global_state = &ctx.accounts.global_state;
+        Ok(PauseStatus {
+            paused: global_state.paused,
+            claims_paused: global_state.claims_paused,
+            any_paused: global_state.paused || global_state.claims_paused,
+        })
+    }
+
+    pub fn get_config(ctx: Context<GetConfig>) -> Result<ConfigView> {
+        let global_state = &ctx.accounts.global_state;
+        Ok(ConfigView {
+            owner: global_state.owner,
+            paused: global_state.paused,
+            supported_token_mints: global_state.supported_token_mints.clone(),
+            min_quest_duration_seconds: global_state.min_quest_duration_seconds,
+            enforce_supported_mint_on_payout: global_state.enforce_supported_mint_on_payout,
+            default_quest_duration_seconds: global_state.default_quest_duration_seconds,
+            global_min_reward: global_state.global_min_reward,
+            large_reward_threshold: global_state.large_reward_threshold,
+            approvers: global_state.approvers.clone(),
+            required_approvals: global_state.required_approvals,
+            claims_paused: global_state.claims_paused,
+            enforce_min_reward_feasibility: global_state.enforce_min_reward_feasibility,
+            max_quest_id_length: global_state.max_quest_id_length,
+            audit_log_initialized: global_state.audit_log_initialized,
+        })
+    }
+
+    pub fn initialize_audit_log(ctx: Context<InitializeAuditLog>) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.global_state.owner,
+            CustomError::UnauthorizedConfigUpdate
+        );
+
+        let audit_log = &mut ctx.accounts.audit_log;
+        audit_log.head = 0;
+        audit_log.total_recorded = 0;
+        audit_log.entries = Vec::new();
+        // From here on, privileged instructions can no longer skip the log
+        ctx.accounts.global_state.audit_log_initialized = true;
+        record_audit_entry(
+            &ctx.accounts.global_state,
+            Some(&mut ctx.accounts.audit_log),
+            ACTION_INITIALIZE_AUDIT_LOG,
+            ctx.accounts.owner.key(),
+        )
+    }
+
+    pub fn get_audit_log(ctx: Context<GetAuditLog>) -> Result<Vec<AuditEntry>> {
+        // Oldest entry first; once wrapped, the oldest sits at `head`
+        let audit_log = &ctx.accounts.audit_log;
+        let mut entries = audit_log.entries.clone();
+        if entries.len() == AUDIT_LOG_CAPACITY {
+            entries.rotate_left(usize::from(audit_log.head));
+        }
+        Ok(entries)
+    }
+
+    pub fn set_owner(ctx: Context<SetOwner>, new_owner: Pubkey) -> Result<()> {
+        // Only current owner can rotate ownership
+        require!(
+            ctx.accounts.current_owner.key() == ctx.accounts.global_state.owner,
+            CustomError::UnauthorizedRewardAction
+        );
+
+        let global_state = &mut ctx.accounts.global_state;
+        if global_state.owner_history.len() == MAX_OWNER_HISTORY {
+            global_state.owner_history.remove(0);
+        }
+        let previous_owner = global_state.owner;
+        global_state.owner_history.push(previous_owner);
+        global_state.owner = new_owner;
+        record_audit_entry(
+            &ctx.accounts.global_state,
+            ctx.accounts.audit_log.as_mut(),
+            ACTION_SET_OWNER,
+            ctx.accounts.current_owner.key(),
+        )
+    }
+
+    pub fn send_reward(ctx: Context<SendReward>, reward_amount: u64) -> Result<()> {
+        pay_reward(ctx, reward_amount, REWARD_REASON_WINNER)
+    }
+
+    pub fn send_reward_weighted(
+        ctx: Context<SendReward>,
+        weight: u64,
+        total_weight: u64,
+        pool: u64,
+    ) -> Result<()> {
+        require!(
+            weight > 0 && total_weight > 0 && weight <= total_weight,
+            CustomError::InvalidWeight
+        );
+        let reward_amount =
+            u64::try_from(u128::from(pool) * u128::from(weight) / u128::from(total_weight))
+                .map_err(|_| CustomError::ArithmeticOverflow)?;
+
+        // The first weighted send fixes the pool; later ones must split the same pool
+        let quest = &mut ctx.accounts.quest;
+        if quest.weighted_pool == 0 {
+            quest.weighted_pool = pool;
+        }
+        require!(
+            quest.weighted_pool == pool,
+            CustomError::WeightedPoolMismatch
+        );
+        quest.weighted_distributed = quest
+            .weighted_distributed
+            .checked_add(reward_amount)
+            .ok_or(CustomError::ArithmeticOverflow)?;
+        require!(
+            quest.weighted_distributed <= quest.weighted_pool,
+            CustomError::InsufficientRewardBalance
+        );
+
+        pay_reward(ctx, reward_amount, REWARD_REASON_WEIGHTED)
+    }
+
+    pub fn send_reward_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SendRewardBatch<'info>>,
+        winners: Vec<Pubkey>,
+        amounts: Vec<u64>,
+    ) -> Result<u32> {
+        validate_reward_sender(
+            &ctx.accounts.global_state,
+            ctx.accounts.owner.key(),
+            &ctx.accounts.quest,
+        )?;
+        require!(winners.len() == amounts.len(), CustomError::InvalidBatch);
+        let amounts =
+            validate_batch_amounts(&ctx.accounts.global_state, &ctx.accounts.quest, &amounts)?;
+        // Each winner contributes a [winner_token_account, reward_claimed] pair
+        require!(
+            ctx.remaining_accounts.len() == winners.len() * 2,
+            CustomError::InvalidBatch
+        );
+
+        let total_amount = amounts
+            .iter()
+            .try_fold(0u64, |acc, amount| acc.checked_add(*amount))
+            .ok_or(CustomError::ArithmeticOverflow)?;
+        ensure_distributable(&ctx.accounts.quest, total_amount)?;
+        let total_winners = u32::try_from(winners.len())
+            .ok()
+            .and_then(|count| ctx.accounts.quest.total_winners.checked_add(count))
+            .ok_or(CustomError::WinnerCountOverflow)?;
+        require!(
+            total_winners <= ctx.accounts.quest.max_winners,
+            CustomError::MaxWinnersReached
+        );
+        ensure_min_reward_feasible(
+            &ctx.accounts.global_state,
+            &ctx.accounts.quest,
+            total_amount,
+            total_winners,
+        )?;
+
+        let quest_key = ctx.accounts.quest.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[GLOBAL_STATE_SEED, &[ctx.bumps.global_state]]];
+
+        let mut processed: u32 = 0;
+        let mut processed_amount: u64 = 0;
+        for (i, (winner, amount)) in winners.iter().zip(amounts.iter()).enumerate() {
+            // Stop before running out of compute; callers resubmit the rest
+            if compute_units::sol_remaining_compute_units() < BATCH_COMPUTE_UNITS_PER_WINNER {
+                break;
+            }
+
+            pay_listed_winner(
+                ctx.accounts,
+                signer_seeds,
+                *winner,
+                *amount,
+                REWARD_REASON_WINNER,
+                &ctx.remaining_accounts[i * 2],
+                &ctx.remaining_accounts[i * 2 + 1],
+            )?;
+
+            processed += 1;
+            processed_amount = processed_amount
+                .checked_add(*amount)
+                .ok_or(CustomError::ArithmeticOverflow)?;
+
+            // The quest is only updated after the loop, so report the running figures
+            let quest = &ctx.accounts.quest;
+            emit!(RewardSent {
+                quest: quest_key,
+                winner: *winner,
+                amount: *amount,
+                remaining_amount: quest
+                    .amount
+                    .saturating_sub(quest.total_reward_distributed)
+                    .saturating_sub(processed_amount),
+                remaining_slots: quest
+                    .max_winners
+                    .saturating_sub(quest.total_winners)
+                    .saturating_sub(processed),
+            });
+        }
+        require!(processed > 0, CustomError::ComputeBudgetExceeded);
+        if (processed as usize) < winners.len() {
+            msg!(
+                "Compute budget reached: processed {} of {} winners",
+                processed,
+                winners.len()
+            );
+        }
+
+        let quest = &mut ctx.accounts.quest;
+        quest.total_reward_distributed = quest
+            .total_reward_distributed
+            .checked_add(processed_amount)
+            .ok_or(CustomError::ArithmeticOverflow)?;
+        quest.total_winners = quest
+            .total_winners
+            .checked_add(processed)
+            .ok_or(CustomError::WinnerCountOverflow)?;
+        record_payout_times(quest)?;
+        ensure_quest_invariants(quest)?;
+
+        record_audit_entry(
+            &ctx.accounts.global_state,
+            ctx.accounts.audit_log.as_mut(),
+            ACTION_SEND_REWARD,
+            ctx.accounts.owner.key(),
+        )?;
+        Ok(processed)
+    }
+
+    pub fn distribute_remaining_equally<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SendRewardBatch<'info>>,
+        winners: Vec<Pubkey>,
+    ) -> Result<()> {
+        validate_reward_sender(
+            &ctx.accounts.global_state,
+            ctx.accounts.owner.key(),
+            &ctx.accounts.quest,
+        )?;
+        require!(!winners.is_empty(), CustomError::InvalidBatch);
+        require!(winners.len() <= MAX_BATCH_SIZE, CustomError::BatchTooLarge);
+        // Each winner contributes a [winner_token_account, reward_claimed] pair
+        require!(
+            ctx.remaining_accounts.len() == winners.len() * 2,
+            CustomError::InvalidBatch
+        );
+
+        let quest = &ctx.accounts.quest;
+        let winner_count =
+            u32::try_from(winners.len()).map_err(|_| CustomError::WinnerCountOverflow)?;
+        let total_winners = quest
+            .total_winners
+            .checked_add(winner_count)
+            .ok_or(CustomError::WinnerCountOverflow)?;
+        require!(
+            total_winners <= quest.max_winners,
+            CustomError::MaxWinnersReached
+        );
+
+        let remaining = quest
+            .amount
+            .checked_sub(quest.total_reward_distributed)
+            .ok_or(CustomError::InvariantViolation)?;
+        // Integer-division dust goes to the first winner so the pool empties exactly
+        let share = remaining / u64::from(winner_count);
+        let dust = remaining % u64::from(winner_count);
+        require!(
+            share > 0 && share >= ctx.accounts.global_state.global_min_reward,
+            CustomError::BelowMinReward
+        );
+        let large_reward_threshold = ctx.accounts.global_state.large_reward_threshold;
+        require!(
+            large_reward_threshold == 0 || share + dust <= large_reward_threshold,
+            CustomError::LargeRewardNotApproved
+        );
+        ensure_within_single_reward_cap(quest, share + dust)?;
+        ensure_distributable(quest, remaining)?;
+        require!(
+            ctx.accounts.escrow_account.amount >= remaining,
+            CustomError::InsufficientRewardBalance
+        );
+
+        let quest_key = quest.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[GLOBAL_STATE_SEED, &[ctx.bumps.global_state]]];
+        let mut paid: u64 = 0;
+        for (i, winner) in winners.iter().enumerate() {
+            let amount = if i == 0 { share + dust } else { share };
+            pay_listed_winner(
+                ctx.accounts,
+                signer_seeds,
+                *winner,
+                amount,
+                REWARD_REASON_EQUAL_SPLIT,
+                &ctx.remaining_accounts[i * 2],
+                &ctx.remaining_accounts[i * 2 + 1],
+            )?;
+            paid += amount;
+            emit!(RewardSent {
+                quest: quest_key,
+                winner: *winner,
+                amount,
+                remaining_amount: remaining - paid,
+                remaining_slots: ctx
+                    .accounts
+                    .quest
+                    .max_winners
+                    .saturating_sub(ctx.accounts.quest.total_winners)
+                    .saturating_sub(i as u32 + 1),
+            });
+        }
+
+        let quest = &mut ctx.accounts.quest;
+        quest.total_reward_distributed = quest.amount;
+        quest.total_winners = total_winners;
+        record_payout_times(quest)?;
+        ensure_quest_invariants(quest)?;
+        // Nothing is left to claim, so the quest is done
+        transition(quest, QuestStatus::Completed)?;
+
+        record_audit_entry(
+            &ctx.accounts.global_state,
+            ctx.accounts.audit_log.as_mut(),
+            ACTION_SEND_REWARD,
+            ctx.accounts.owner.key(),
+        )
+    }
+
+    pub fn claim_remaining_reward(ctx: Context<ClaimRemainingReward>) -> Result<()> {
+        require!(
+            !ctx.accounts.global_state.paused,
+            CustomError::ContractPaused
+        );
+        require!(
+            !ctx.accounts.global_state.claims_paused,
+            CustomError::ClaimsPaused
+        );
+
+        let quest = &mut ctx.accounts.quest;
+
+        // Only quest creator or admin can call this function
+        require!(
+            quest.creator == ctx.accounts.claimer.key()
+                || ctx.accounts.claimer.key() == ctx.accounts.global_state.owner,
+            CustomError::UnauthorizedWithdrawal
+        );
+
+        // Quest must be inactive (ended)
+        require!(!quest.is_active, CustomError::QuestNotActive);
+        require!(!quest.paused, CustomError::QuestPaused);
+        require!(!quest.owner_frozen, CustomError::QuestFrozen);
+
+        // Must wait 1 week after quest deadline
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        require!(
+            current_timestamp >= quest.deadline.saturating_add(REMAINING_CLAIM_DELAY_SECONDS),
+            CustomError::WithdrawalTooEarly
+        );
+
+        // Calculate remaining unclaimed amount
+        let remaining_amount = quest
+            .amount
+            .checked_sub(quest.total_reward_distributed)
+            .ok_or(CustomError::ArithmeticOverflow)?;
+        require!(remaining_amount > 0, CustomError::NoTokensToWithdraw);
+        // Fail cleanly, before touching quest state, if the escrow was drained
+        require!(
+            ctx.accounts.escrow_account.amount >= remaining_amount,
+            CustomError::InsufficientRewardBalance
+        );
+
+        // Update the quest to prevent double claiming by setting amount to distributed amount
+        quest.amount = quest.total_reward_distributed;
+        transition(quest, QuestStatus::Completed)?;
+        let burned = quest.leftover_policy == LeftoverPolicy::Burn;
+
+        let signer_seeds: &[&[&[u8]]] = &[&[GLOBAL_STATE_SEED, &[ctx.bumps.global_state]]];
+        let recipient = if burned {
+            // The escrow authority burns its own balance, so no mint authority
+            // is needed; any recipient account is ignored
+            let token_mint = ctx
+                .accounts
+                .token_mint
+                .as_ref()
+                .ok_or(CustomError::InvalidTokenMint)?;
+            let burn_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint: token_mint.to_account_info(),
+                    from: ctx.accounts.escrow_account.to_account_info(),
+                    authority: ctx.accounts.global_state.to_account_info(),
+                },
+                signer_seeds,
+            );
+            token::burn(burn_ctx, remaining_amount)?;
+            Pubkey::default()
+        } else {
+            // The creator may send their refund elsewhere; the owner claiming on
+            // their behalf always pays the creator's own account
+            let destination = match &ctx.accounts.recipient_token_account {
+                Some(recipient_token_account) => {
+                    require!(
+                        ctx.accounts.claimer.key() == ctx.accounts.quest.creator,
+                        CustomError::UnauthorizedRecipient
+                    );
+                    require_keys_neq!(
+                        recipient_token_account.key(),
+                        ctx.accounts.escrow_account.key(),
+                        CustomError::InvalidTokenAccount
+                    );
+                    recipient_token_account.to_account_info()
+                }
+                None => ctx.accounts.creator_token_account.to_account_info(),
+            };
+
+            // Transfer remaining tokens to the creator or their chosen recipient
+            let transfer_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.escrow_account.to_account_info(),
+                    to: destination.clone(),
+                    authority: ctx.accounts.global_state.to_account_info(),
+                },
+                signer_seeds,
+            );
+            token::transfer(transfer_ctx, remaining_amount)?;
+            destination.key()
+        };
+
+        emit!(RemainingRewardClaimed {
+            quest: ctx.accounts.quest.key(),
+            claimer: ctx.accounts.claimer.key(),
+            amount: remaining_amount,
+            timestamp: current_timestamp,
+            by_creator: ctx.accounts.claimer.key() == ctx.accounts.quest.creator,
+            recipient,
+            burned,
+        });
+
+        if ctx.accounts.claimer.key() == ctx.accounts.global_state.owner {
+            record_audit_entry(
+                &ctx.accounts.global_state,
+                ctx.accounts.audit_log.as_mut(),
+                ACTION_CLAIM_REMAINING,
+                ctx.accounts.claimer.key(),
+            )?;
+        }
+        Ok(())
+    }
+
+    pub fn close_reward_claimed_for_cancelled<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CloseCancelledRewardClaimed<'info>>,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.global_state.owner,
+            CustomError::UnauthorizedClose
+        );
+        require!(ctx.accounts.quest.cancelled, CustomError::QuestNotCancelled);
+        // Each record is followed by its winner, who receives the rent
+        let pairs = ctx.remaining_accounts.chunks_exact(2);
+        require!(
+            !ctx.remaining_accounts.is_empty() && pairs.remainder().is_empty(),
+            CustomError::InvalidRewardClaimedAccount
+        );
+
+        let quest_key = ctx.accounts.quest.key();
+        for pair in pairs {
+            let reward_claimed: Account<'info, RewardClaimed> = Account::try_from(&pair[0])?;
+            require_keys_eq!(
+                reward_claimed.quest,
+                quest_key,
+                CustomError::InvalidRewardClaimedAccount
+            );
+            // The record may have been funded by the winner or anyone else
+            // through `payer`, so the owner never keeps its rent
+            require_keys_eq!(
+                pair[1].key(),
+                reward_claimed.winner,
+                CustomError::InvalidRentRecipient
+            );
+            reward_claimed.close(pair[1].clone())?;
+        }
+        ctx.accounts.quest.records_closed = true;
+        record_audit_entry(
+            &ctx.accounts.global_state,
+            ctx.accounts.audit_log.as_mut(),
+            ACTION_CLOSE_REWARD_RECORDS,
+            ctx.accounts.owner.key(),
+        )
+    }
+
+    pub fn migrate_reward_claimed(ctx: Context<MigrateRewardClaimed>) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.global_state.owner,
+            CustomError::UnauthorizedConfigUpdate
+        );
+
+        let reward_claimed_info = ctx.accounts.reward_claimed.to_account_info();
+        let legacy = {
+            let data = reward_claimed_info.try_borrow_data()?;
+            require!(
+                data.len() >= LEGACY_REWARD_CLAIMED_SPACE
+                    && data[..DISCRIMINATOR_SIZE] == *RewardClaimed::DISCRIMINATOR,
+                CustomError::InvalidRewardClaimedAccount
+            );
+            // Records already in the current layout carry their version last
+            require!(
+                data.len() < REWARD_CLAIMED_SPACE
+                    || data[REWARD_CLAIMED_SPACE - 1] < REWARD_CLAIMED_VERSION,
+                CustomError::AlreadyMigrated
+            );
+            LegacyRewardClaimed::deserialize(&mut &data[DISCRIMINATOR_SIZE..])
+                .map_err(|_| CustomError::InvalidRewardClaimedAccount)?
+        };
+
+        let (expected_reward_claimed, bump) = Pubkey::find_program_address(
+            &[
+                b"reward_claimed",
+                legacy.quest.as_ref(),
+                legacy.winner.as_ref(),
+            ],
+            ctx.program_id,
+        );
+        require_keys_eq!(
+            reward_claimed_info.key(),
+            expected_reward_claimed,
+            CustomError::InvalidRewardClaimedAccount
+        );
+
+        // The owner tops up the rent for the larger layout
+        resize_account(
+            &reward_claimed_info,
+            &ctx.accounts.owner.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            REWARD_CLAIMED_SPACE,
+        )?;
+
+        // Legacy records predate weighted sends, so every one paid a winner directly
+        let reward_claimed = RewardClaimed {
+            quest: legacy.quest,
+            winner: legacy.winner,
+            reward_amount: legacy.reward_amount,
+            claimed: legacy.claimed,
+            bump,
+            reason: REWARD_REASON_WINNER,
+            version: REWARD_CLAIMED_VERSION,
+        };
+        reward_claimed.try_serialize(&mut &mut reward_claimed_info.try_borrow_mut_data()?[..])?;
+        record_audit_entry(
+            &ctx.accounts.global_state,
+            ctx.accounts.audit_log.as_mut(),
+            ACTION_MIGRATE,
+            ctx.accounts.owner.key(),
+        )
+    }
+
+    pub fn migrate_global_state(ctx: Context<MigrateGlobalState>) -> Result<()> {
+        let global_state_info = ctx.accounts.global_state.to_account_info();
+        let mut global_state: GlobalState =
+            read_grown_account(&global_state_info, GLOBAL_STATE_SPACE)?;
+        require!(
+            ctx.accounts.owner.key() == global_state.owner,
+            CustomError::UnauthorizedConfigUpdate
+        );
+
+        // Zero would reject every id; accounts older than the soft cap get the
+        // space-derived maximum `initialize` uses
+        if global_state.max_quest_id_length == 0 {
+            global_state.max_quest_id_length = MAX_QUEST_ID_LENGTH as u8;
+        }
+
+        resize_account(
+            &global_state_info,
+            &ctx.accounts.owner.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            GLOBAL_STATE_SPACE + global_state.blocklist.len() * PUBKEY_SIZE,
+        )?;
+        global_state.try_serialize(&mut &mut global_state_info.try_borrow_mut_data()?[..])?;
+        record_audit_entry(
+            &global_state,
+            ctx.accounts.audit_log.as_mut(),
+            ACTION_MIGRATE,
+            ctx.accounts.owner.key(),
+        )
+    }
+
+    pub fn migrate_quest(ctx: Context<MigrateQuest>) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.global_state.owner,
+            CustomError::UnauthorizedConfigUpdate
+        );
+
+        // Every field added since the original layout defaults to zero, which
+        // matches what `create_quest` sets
+        let quest_info = ctx.accounts.quest.to_account_info();
+        let quest: Quest = read_grown_account(&quest_info, QUEST_SPACE)?;
+        resize_account(
+            &quest_info,
+            &ctx.accounts.owner.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            QUEST_SPACE,
+        )?;
+        quest.try_serialize(&mut &mut quest_info.try_borrow_mut_data()?[..])?;
+        record_audit_entry(
+            &ctx.accounts.global_state,
+            ctx.accounts.audit_log.as_mut(),
+            ACTION_MIGRATE,
+            ctx.accounts.owner.key(),
+        )
+    }
+
+    pub fn close_reward_claimed(ctx: Context<CloseRewardClaimed>) -> Result<()> {
+        // Closing frees the record's PDA for `init`, which would let a still
+        // open quest pay the same winner again
+        require!(
+            matches!(
+                QuestStatus::of(&ctx.accounts.quest),
+                QuestStatus::Cancelled | QuestStatus::Completed
+            ),
+            CustomError::QuestStillOpen
+        );
+        let closer = ctx.accounts.closer.key();
+        let winner = ctx.accounts.reward_claimed.winner;
+
+        // A winner closing their own record always gets the rent back; only the
+        // owner may route it to an arbitrary recipient (defaulting to themselves).
+        let recipient = if closer == winner {
+            if let Some(recipient) = &ctx.accounts.recipient {
+                require!(recipient.key() == winner, CustomError::InvalidRentRecipient);
+            }
+            ctx.accounts.closer.to_account_info()
+        } else {
+            require!(
+                closer == ctx.accounts.global_state.owner,
+                CustomError::UnauthorizedClose
+            );
+            record_audit_entry(
+                &ctx.accounts.global_state,
+                ctx.accounts.audit_log.as_mut(),
+                ACTION_CLOSE_REWARD_CLAIMED,
+                closer,
+            )?;
+            match &ctx.accounts.recipient {
+                Some(recipient) => recipient.to_account_info(),
+                None => ctx.accounts.closer.to_account_info(),
+            }
+        };
+
+        ctx.accounts.quest.records_closed = true;
+        ctx.accounts.reward_claimed.close(recipient)
+    }
+}
+
+/// Guards shared by every owner-driven payout instruction.
+fn validate_reward_sender(global_state: &GlobalState, sender: Pubkey, quest: &Quest) -> Result<()> {
+    require!(!global_state.paused, CustomError::ContractPaused);
+    require!(!global_state.claims_paused, CustomError::ClaimsPaused);
+    require!(
+        sender == global_state.owner,
+        CustomError::UnauthorizedRewardAction
+    );
+    require!(quest.is_active, CustomError::QuestNotActive);
+    require!(!quest.paused, CustomError::QuestPaused);
+    require!(!quest.owner_frozen, CustomError::QuestFrozen);
+    // Mints de-listed after the quest was created keep paying out unless the
+    // owner opts into re-checking support at payout time
+    if global_state.enforce_supported_mint_on_payout {
+        require!(
+            global_state
+                .supported_token_mints
+                .contains(&quest.token_mint),
+            CustomError::UnsupportedTokenMint
+        );
+    }
+    Ok(())
+}
+
+/// Shared payout path for `send_reward` and `send_reward_weighted`; `reason`
+/// records which of them paid the winner.
+fn pay_reward(ctx: Context<SendReward>, reward_amount: u64, reason: u8) -> Result<()> {
+    validate_reward_sender(
+        &ctx.accounts.global_state,
+        ctx.accounts.owner.key(),
+        &ctx.accounts.quest,
+    )?;
+
+    require!(
+        reward_amount >= ctx.accounts.global_state.global_min_reward,
+        CustomError::BelowMinReward
+    );
+    ensure_large_reward_approved(
+        &ctx.accounts.global_state,
+        ctx.accounts.reward_approval.as_ref(),
+        ctx.accounts.quest.key(),
+        ctx.accounts.winner.key(),
+        reward_amount,
+    )?;
+    let reward_amount = apply_early_bonus(&ctx.accounts.quest, reward_amount)?;
+
+    let quest = &mut ctx.accounts.quest;
+    ensure_within_single_reward_cap(quest, reward_amount)?;
+    ensure_distributable(quest, reward_amount)?;
+    require!(
+        quest.total_winners < quest.max_winners,
+        CustomError::MaxWinnersReached
+    );
+    ensure_min_reward_feasible(
+        &ctx.accounts.global_state,
+        quest,
+        reward_amount,
+        quest.total_winners + 1,
+    )?;
+
+    require!(
+        !ctx.accounts
+            .global_state
+            .blocklist
+            .contains(&ctx.accounts.winner.key()),
+        CustomError::AddressBlocked
+    );
+    ensure_winner_not_program(ctx.accounts.winner.key())?;
+    // Paying the escrow back into itself would corrupt quest accounting
+    require_keys_neq!(
+        ctx.accounts.winner_token_account.key(),
+        ctx.accounts.escrow_account.key(),
+        CustomError::InvalidTokenAccount
+    );
+    validate_winner_token_account(
+        quest,
+        ctx.accounts.winner.key(),
+        &ctx.accounts.winner_token_account,
+    )?;
+
+    // Check if winner has already claimed reward
+    let reward_claimed_pda = &mut ctx.accounts.reward_claimed;
+    require!(!reward_claimed_pda.claimed, CustomError::AlreadyRewarded);
+
+    // Update quest state
+    quest.total_reward_distributed += reward_amount;
+    quest.total_winners = quest
+        .total_winners
+        .checked_add(1)
+        .ok_or(CustomError::WinnerCountOverflow)?;
+    record_payout_times(quest)?;
+    emit!(RewardSent {
+        quest: quest.key(),
+        winner: ctx.accounts.winner.key(),
+        amount: reward_amount,
+        remaining_amount: quest.amount.saturating_sub(quest.total_reward_distributed),
+        remaining_slots: quest.max_winners.saturating_sub(quest.total_winners),
+    });
+
+    // Initialize reward claimed account
+    // Note: quest.id is String, but RewardClaimed.quest stores Pubkey for consistency
+    reward_claimed_pda.quest = ctx.accounts.quest.key(); // Using quest.key() (Pubkey) instead of quest.id (String)
+    reward_claimed_pda.winner = ctx.accounts.winner.key();
+    reward_claimed_pda.reward_amount = reward_amount;
+    reward_claimed_pda.claimed = true;
+    reward_claimed_pda.bump = ctx.bumps.reward_claimed;
+    reward_claimed_pda.reason = reason;
+    reward_claimed_pda.version = REWARD_CLAIMED_VERSION;
+
+    // Transfer reward tokens from escrow to winner
+    let signer_seeds: &[&[&[u8]]] = &[&[GLOBAL_STATE_SEED, &[ctx.bumps.global_state]]];
+    let transfer_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.escrow_account.to_account_info(),
+            to: ctx.accounts.winner_token_account.to_account_info(),
+            authority: ctx.accounts.global_state.to_account_info(),
+        },
+        signer_seeds,
+    );
+    token::transfer(transfer_ctx, reward_amount)?;
+
+    ensure_quest_invariants(&ctx.accounts.quest)?;
+    // An approval is single-use; its rent goes back to the approver who proposed it
+    if let Some(reward_approval) = &ctx.accounts.reward_approval {
+        let proposer = ctx
+            .accounts
+            .proposer
+            .as_ref()
+            .ok_or(CustomError::InvalidRentRecipient)?;
+        require_keys_eq!(
+            proposer.key(),
+            reward_approval.proposer,
+            CustomError::InvalidRentRecipient
+        );
+        reward_approval.close(proposer.to_account_info())?;
+    }
+    record_audit_entry(
+        &ctx.accounts.global_state,
+        ctx.accounts.audit_log.as_mut(),
+        ACTION_SEND_REWARD,
+        ctx.accounts.owner.key(),
+    )
+}
+
+/// Ensures `reward_amount` more can be paid from the quest, keeping the reserve
+/// locked until the deadline has passed.
+fn ensure_distributable(quest: &Quest, reward_amount: u64) -> Result<()> {
+    let distributed_after = quest
+        .total_reward_distributed
+        .checked_add(reward_amount)
+        .ok_or(CustomError::InsufficientRewardBalance)?;
+    require!(
+        distributed_after <= quest.amount,
+        CustomError::InsufficientRewardBalance
+    );
+
+    if Clock::get()?.unix_timestamp < quest.deadline {
+        require!(
+            distributed_after <= quest.amount.saturating_sub(quest.reserve_amount),
+            CustomError::ReserveLocked
+        );
+    }
+    Ok(())
+}
+
+/// Checks that after paying `reward_amount`, leaving the quest at
+/// `winners_after` winners, every open slot can still get `global_min_reward`.
+/// Only logs a warning unless the owner made the check strict.
+fn ensure_min_reward_feasible(
+    global_state: &GlobalState,
+    quest: &Quest,
+    reward_amount: u64,
+    winners_after: u32,
+) -> Result<()> {
+    let remaining_after = quest
+        .amount
+        .saturating_sub(quest.total_reward_distributed)
+        .saturating_sub(reward_amount);
+    let open_slots = quest.max_winners.saturating_sub(winners_after);
+    let needed = u128::from(open_slots) * u128::from(global_state.global_min_reward);
+    if u128::from(remaining_after) >= needed {
+        return Ok(());
+    }
+
+    require!(
+        !global_state.enforce_min_reward_feasibility,
+        CustomError::MinRewardInfeasible
+    );
+    msg!(
+        "Warning: {} left cannot pay {} open slots the minimum reward",
+        remaining_after,
+        open_slots
+    );
+    Ok(())
+}
+
+/// Post-condition for payout paths: a logic bug must never let a quest pay out
+/// more than it was funded with.
+fn ensure_quest_invariants(quest: &Quest) -> Result<()> {
+    require!(
+        quest.total_reward_distributed <= quest.amount,
+        CustomError::InvariantViolation
+    );
+    Ok(())
+}
+
+/// Stamps the first payout and the payout that exhausts the quest's winner
+/// slots or funds, for payout velocity reporting.
+fn record_payout_times(quest: &mut Quest) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    if quest.first_reward_at == 0 {
+        quest.first_reward_at = now;
+    }
+    if quest.completed_at == 0
+        && (quest.total_winners >= quest.max_winners
+            || quest.total_reward_distributed >= quest.amount)
+    {
+        quest.completed_at = now;
+    }
+    Ok(())
+}
+
+/// Payouts above `large_reward_threshold` need a matching approval signed off
+/// by enough of the current approvers.
+fn ensure_large_reward_approved(
+    global_state: &GlobalState,
+    reward_approval: Option<&Account<RewardApproval>>,
+    quest: Pubkey,
+    winner: Pubkey,
+    reward_amount: u64,
+) -> Result<()> {
+    let threshold = global_state.large_reward_threshold;
+    if threshold == 0 || reward_amount <= threshold {
+        return Ok(());
+    }
+
+    let reward_approval = reward_approval.ok_or(CustomError::LargeRewardNotApproved)?;
+    require!(
+        reward_approval.quest == quest
+            && reward_approval.winner == winner
+            && reward_approval.amount == reward_amount,
+        CustomError::InvalidApproval
+    );
+    // Approvals from signers since removed from the set no longer count
+    let approvals = reward_approval
+        .approvals
+        .iter()
+        .filter(|approver| global_state.approvers.contains(approver))
+        .count();
+    require!(
+        approvals >= usize::from(global_state.required_approvals),
+        CustomError::LargeRewardNotApproved
+    );
+    Ok(())
+}
+
+/// Scales a payout by the quest's early-winner bonus while the bonus window
+/// is open; the extra is charged against the same escrow.
+fn apply_early_bonus(quest: &Quest, reward_amount: u64) -> Result<u64> {
+    if quest.bonus_bps == 0 || Clock::get()?.unix_timestamp > quest.bonus_until {
+        return Ok(reward_amount);
+    }
+
+    let boosted = u128::from(reward_amount)
+        * (u128::from(BPS_DENOMINATOR) + u128::from(quest.bonus_bps))
+        / u128::from(BPS_DENOMINATOR);
+    Ok(u64::try_from(boosted).map_err(|_| CustomError::ArithmeticOverflow)?)
+}
+
+/// Per-amount checks shared by `send_reward_batch` and `preview_batch`.
+/// Returns the amounts with any early bonus applied.
+fn validate_batch_amounts(
+    global_state: &GlobalState,
+    quest: &Quest,
+    amounts: &[u64],
+) -> Result<Vec<u64>> {
+    require!(!amounts.is_empty(), CustomError::InvalidBatch);
+    require!(amounts.len() <= MAX_BATCH_SIZE, CustomError::BatchTooLarge);
+
+    let large_reward_threshold = global_state.large_reward_threshold;
+    for amount in amounts {
+        require!(
+            *amount >= global_state.global_min_reward,
+            CustomError::BelowMinReward
+        );
+        // Batches cannot carry approvals, so large payouts go through send_reward
+        require!(
+            large_reward_threshold == 0 || *amount <= large_reward_threshold,
+            CustomError::LargeRewardNotApproved
+        );
+    }
+    let amounts = amounts
+        .iter()
+        .map(|amount| apply_early_bonus(quest, *amount))
+        .collect::<Result<Vec<u64>>>()?;
+    for amount in &amounts {
+        ensure_within_single_reward_cap(quest, *amount)?;
+    }
+    Ok(amounts)
+}
+
+/// Caps a single payout to `max_single_reward_bps` of what is left in the pool.
+fn ensure_within_single_reward_cap(quest: &Quest, reward_amount: u64) -> Result<()> {
+    if quest.max_single_reward_bps == 0 {
+        return Ok(());
+    }
+
+    let remaining = quest.amount.saturating_sub(quest.total_reward_distributed);
+    let cap = u128::from(remaining) * u128::from(quest.max_single_reward_bps)
+        / u128::from(BPS_DENOMINATOR);
+    require!(
+        u128::from(reward_amount) <= cap,
+        CustomError::RewardExceedsSingleCap
+    );
+    Ok(())
+}
+
+/// Rejects well-known program ids as winners; records keyed on them are meaningless.
+fn ensure_winner_not_program(winner: Pubkey) -> Result<()> {
+    let reserved = [
+        system_program::ID,
+        token::ID,
+        TOKEN_2022_PROGRAM_ID,
+        ASSOCIATED_TOKEN_PROGRAM_ID,
+        crate::ID,
+    ];
+    require!(!reserved.contains(&winner), CustomError::InvalidWinner);
+    Ok(())
+}
+
+/// Validates the winner token account up front so a bad account surfaces as a
+/// clear error rather than a failed transfer CPI.
+fn validate_winner_token_account(
+    quest: &Quest,
+    winner: Pubkey,
+    winner_token: &TokenAccount,
+) -> Result<()> {
+    require!(
+        winner_token.mint == quest.token_mint,
+        CustomError::MissingAssociatedTokenAccount
+    );
+    require!(
+        winner_token.owner == winner,
+        CustomError::MissingAssociatedTokenAccount
+    );
+    // A frozen account would otherwise fail deep inside the token CPI
+    require!(
+        winner_token.state == AccountState::Initialized,
+        CustomError::FrozenAccount
+    );
+    Ok(())
+}
+
+/// Creates a PDA owned by `owner`, tolerating lamports already sent to its address.
+fn create_pda_account<'info>(
+    payer: &AccountInfo<'info>,
+    target: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    space: usize,
+    owner: &Pubkey,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let required_lamports = Rent::get()?.minimum_balance(space);
+    let current_lamports = target.lamports();
+
+    if current_lamports == 0 {
+        return system_program::create_account(
+            CpiContext::new_with_signer(
+                system_program.clone(),
+                system_program::CreateAccount {
+                    from: payer.clone(),
+                    to: target.clone(),
+                },
+                signer_seeds,
+            ),
+            required_lamports,
+            space as u64,
+            owner,
+        );
+    }
+
+    if required_lamports > current_lamports {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                system_program::Transfer {
+                    from: payer.clone(),
+                    to: target.clone(),
+                },
+            ),
+            required_lamports - current_lamports,
+        )?;
+    }
+    system_program::allocate(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            system_program::Allocate {
+                account_to_allocate: target.clone(),
+            },
+            signer_seeds,
+        ),
+        space as u64,
+    )?;
+    system_program::assign(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            system_program::Assign {
+                account_to_assign: target.clone(),
+            },
+            signer_seeds,
+        ),
+        owner,
+    )
+}
+
+/// Reads an account written under an older, shorter layout of `T`. Layouts
+/// only ever grow by appending fields, so the missing tail reads as zeroes.
+fn read_grown_account<T: AccountDeserialize>(account: &AccountInfo, space: usize) -> Result<T> {
+    let data = account.try_borrow_data()?;
+    require!(data.len() < space, CustomError::AlreadyMigrated);
+    let mut padded = data.to_vec();
+    padded.resize(space, 0);
+    T::try_deserialize(&mut padded.as_slice())
+}
+
+/// Grows a program-owned account to `space`, with `payer` topping up its rent.
+fn resize_account<'info>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    space: usize,
+) -> Result<()> {
+    let required_lamports = Rent::get()?.minimum_balance(space);
+    let current_lamports = account.lamports();
+    if required_lamports > current_lamports {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                system_program::Transfer {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+            ),
+            required_lamports - current_lamports,
+        )?;
+    }
+    account.resize(space)?;
+    Ok(())
+}
+
+/// Appends to the audit log, overwriting the oldest entry once the buffer is
+/// full. The log may only be left out before `initialize_audit_log` has run.
+fn record_audit_entry(
+    global_state: &GlobalState,
+    audit_log: Option<&mut Account<AuditLog>>,
+    action_code: u8,
+    actor: Pubkey,
+) -> Result<()> {
+    let Some(audit_log) = audit_log else {
+        require!(
+            !global_state.audit_log_initialized,
+            CustomError::AuditLogRequired
+        );
+        return Ok(());
+    };
+
+    let entry = AuditEntry {
+        action_code,
+        actor,
+        timestamp: Clock::get()?.unix_timestamp,
+    };
+    if audit_log.entries.len() < AUDIT_LOG_CAPACITY {
+        audit_log.entries.push(entry);
+    } else {
+        let head = usize::from(audit_log.head);
+        audit_log.entries[head] = entry;
+        audit_log.head = ((head + 1) % AUDIT_LOG_CAPACITY) as u16;
+    }
+    audit_log.total_recorded = audit_log.total_recorded.saturating_add(1);
+    Ok(())
+}
+
+/// Creates and initializes a quest escrow token account at its PDA.
+fn create_escrow_account<'info>(
+    payer: &AccountInfo<'info>,
+    escrow: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    escrow_seeds: &[&[u8]],
+) -> Result<()> {
+    // A stale escrow at the derived address would otherwise surface as an
+    // opaque "already in use" error from the system program
+    require!(
+        escrow.data_is_empty() && escrow.owner == &system_program::ID,
+        CustomError::EscrowAlreadyExists
+    );
+    create_pda_account(
+        payer,
+        escrow,
+        system_program,
+        TokenAccount::LEN,
+        &token::ID,
+        &[escrow_seeds],
+    )?;
+    token::initialize_account3(CpiContext::new(
+        token_program.clone(),
+        InitializeAccount3 {
+            account: escrow.clone(),
+            mint: mint.clone(),
+            authority: authority.clone(),
+        },
+    ))
+}
+
+/// Pays one winner of a listed payout from `[winner_token_account,
+/// reward_claimed]` remaining accounts, creating their RewardClaimed record.
+/// Quest counters and events are left to the caller.
+fn pay_listed_winner<'info>(
+    accounts: &SendRewardBatch<'info>,
+    signer_seeds: &[&[&[u8]]],
+    winner: Pubkey,
+    amount: u64,
+    reason: u8,
+    winner_token_info: &'info AccountInfo<'info>,
+    reward_claimed_info: &'info AccountInfo<'info>,
+) -> Result<()> {
+    let quest_key = accounts.quest.key();
+
+    require!(
+        !accounts.global_state.blocklist.contains(&winner),
+        CustomError::AddressBlocked
+    );
+    ensure_winner_not_program(winner)?;
+    require_keys_neq!(
+        winner_token_info.key(),
+        accounts.escrow_account.key(),
+        CustomError::InvalidTokenAccount
+    );
+    let winner_token: Account<'info, TokenAccount> = Account::try_from(winner_token_info)?;
+    validate_winner_token_account(&accounts.quest, winner, &winner_token)?;
+
+    let (expected_reward_claimed, bump) = Pubkey::find_program_address(
+        &[b"reward_claimed", quest_key.as_ref(), winner.as_ref()],
+        &crate::ID,
+    );
+    require_keys_eq!(
+        reward_claimed_info.key(),
+        expected_reward_claimed,
+        CustomError::InvalidRewardClaimedAccount
+    );
+    // A program-owned record means this winner was already paid
+    require!(
+        reward_claimed_info.owner != &crate::ID,
+        CustomError::AlreadyRewarded
+    );
+
+    let reward_claimed_seeds: &[&[&[u8]]] = &[&[
+        b"reward_claimed",
+        quest_key.as_ref(),
+        winner.as_ref(),
+        &[bump],
+    ]];
+    create_pda_account(
+        &accounts.owner.to_account_info(),
+        reward_claimed_info,
+        &accounts.system_program.to_account_info(),
+        REWARD_CLAIMED_SPACE,
+        &crate::ID,
+        reward_claimed_seeds,
+    )?;
+    let reward_claimed = RewardClaimed {
+        quest: quest_key,
+        winner,
+        reward_amount: amount,
+        claimed: true,
+        bump,
+        reason,
+        version: REWARD_CLAIMED_VERSION,
+    };
+    reward_claimed.try_serialize(&mut &mut reward_claimed_info.try_borrow_mut_data()?[..])?;
+
+    let transfer_ctx = CpiContext::new_with_signer(
+        accounts.token_program.to_account_info(),
+        Transfer {
+            from: accounts.escrow_account.to_account_info(),
+            to: winner_token_info.clone(),
+            authority: accounts.global_state.to_account_info(),
+        },
+        signer_seeds,
+    );
+    token::transfer(transfer_ctx, amount)?;
+    Ok(())
+}
+
+/// Moves a quest along its lifecycle, rejecting transitions the lifecycle does
+/// not allow.
+fn transition(quest: &mut Quest, to: QuestStatus) -> Result<()> {
+    match (QuestStatus::of(quest), to) {
+        (QuestStatus::Active | QuestStatus::Paused, QuestStatus::Inactive) => {
+            quest.is_active = false;
+        }
+        (QuestStatus::Inactive | QuestStatus::Cancelled, QuestStatus::Active) => {
+            quest.is_active = true;
+            quest.cancelled = false;
+        }
+        (QuestStatus::Active | QuestStatus::Paused, QuestStatus::Cancelled) => {
+            quest.is_active = false;
+            quest.cancelled = true;
+        }
+        (QuestStatus::Active | QuestStatus::Inactive, QuestStatus::Completed) => {
+            quest.is_active = false;
+            quest.finalized = true;
+        }
+        // `Paused` is never a target; `pause_quests` sets the flag directly
+        _ => return err!(CustomError::InvalidStateTransition),
+    }
+    Ok(())
+}
+
+/// Sets the per-quest pause flag on every quest passed in `remaining_accounts`.
+fn set_quests_paused<'info>(quests: &'info [AccountInfo<'info>], paused: bool) -> Result<()> {
+    require!(!quests.is_empty(), CustomError::NoQuestsProvided);
+
+    for quest_info in quests {
+        let mut quest: Account<'info, Quest> = Account::try_from(quest_info)?;
+        quest.paused = paused;
+        quest.exit(&crate::ID)?;
+    }
+    Ok(())
+}
+
+#[error_code]
+pub enum CustomError {
+    #[msg("Contract is paused")]
+    ContractPaused,
+    #[msg("Unsupported token mint")]
+    UnsupportedTokenMint,
+    #[msg("Unauthorized cancellation")]
+    UnauthorizedCancellation,
+    #[msg("Quest is not active")]
+    QuestNotActive,
+    #[msg("Quest already cancelled")]
+    QuestAlreadyCancelled,
+    #[msg("Unauthorized status update")]
+    UnauthorizedStatusUpdate,
     #[msg("Unauthorized token modification")]
     UnauthorizedTokenModification,
     #[msg("Token already supported")]
@@ -348,118 +2907,687 @@ pub enum CustomError {
     WithdrawalTooEarly,
     #[msg("Missing associated token account (ATA) for the provided owner/mint. Please create the ATA before sending rewards.")]
     MissingAssociatedTokenAccount,
+    #[msg("Unauthorized close of reward record")]
+    UnauthorizedClose,
+    #[msg("Rent from a winner-closed reward record must return to the winner")]
+    InvalidRentRecipient,
+    #[msg("Winner token account is frozen")]
+    FrozenAccount,
+    #[msg("Quest is paused")]
+    QuestPaused,
+    #[msg("No quest accounts provided")]
+    NoQuestsProvided,
+    #[msg("Unauthorized quest update")]
+    UnauthorizedQuestUpdate,
+    #[msg("Reserve amount exceeds quest amount")]
+    InvalidReserveAmount,
+    #[msg("Reward would dip into the reserve before the quest deadline")]
+    ReserveLocked,
+    #[msg("Arithmetic overflow")]
+    ArithmeticOverflow,
+    #[msg("Batch winners, amounts and accounts do not line up")]
+    InvalidBatch,
+    #[msg("Reward claimed account does not match the winner PDA")]
+    InvalidRewardClaimedAccount,
+    #[msg("Unauthorized config update")]
+    UnauthorizedConfigUpdate,
+    #[msg("Invalid config value")]
+    InvalidConfigValue,
+    #[msg("Quest duration is below the configured minimum")]
+    QuestTooShort,
+    #[msg("Address is blocked from receiving rewards")]
+    AddressBlocked,
+    #[msg("Address is already blocked")]
+    AddressAlreadyBlocked,
+    #[msg("Address is not blocked")]
+    AddressNotBlocked,
+    #[msg("Reward exceeds the per-send cap for this quest")]
+    RewardExceedsSingleCap,
+    #[msg("Quest has not been cancelled")]
+    QuestNotCancelled,
+    #[msg("Invalid token account")]
+    InvalidTokenAccount,
+    #[msg("Not enough compute budget left to pay a single batch winner")]
+    ComputeBudgetExceeded,
+    #[msg("Escrow still holds tokens")]
+    EscrowNotEmpty,
+    #[msg("Amount must be greater than zero")]
+    InvalidAmount,
+    #[msg("Unknown action code")]
+    UnknownAction,
+    #[msg("Invalid deadline")]
+    InvalidDeadline,
+    #[msg("Quest is finalized")]
+    QuestFinalized,
+    #[msg("Quest payouts are frozen by the owner")]
+    QuestFrozen,
+    #[msg("An account already exists at the escrow address; close the stale escrow first")]
+    EscrowAlreadyExists,
+    #[msg("Weight must be non-zero and at most the total weight")]
+    InvalidWeight,
+    #[msg("Pool does not match the quest's weighted pool")]
+    WeightedPoolMismatch,
+    #[msg("Max winners cannot be below the winners already paid")]
+    MaxWinnersBelowTotal,
+    #[msg("Quest accounting invariant violated")]
+    InvariantViolation,
+    #[msg("Quest id exceeds the maximum length")]
+    QuestIdTooLong,
+    #[msg("Split amount or resulting winner slots are invalid")]
+    InvalidSplit,
+    #[msg("Quests cannot be merged")]
+    InvalidMerge,
+    #[msg("Invalid token mint")]
+    InvalidTokenMint,
+    #[msg("Too many supported token mints")]
+    TooManyTokenMints,
+    #[msg("Only the program upgrade authority can initialize")]
+    UnauthorizedInit,
+    #[msg("Amount is below the platform minimum reward")]
+    BelowMinReward,
+    #[msg("Signer is not an approver")]
+    UnauthorizedApprover,
+    #[msg("Approver has already approved")]
+    AlreadyApproved,
+    #[msg("Reward above the threshold lacks enough approvals")]
+    LargeRewardNotApproved,
+    #[msg("Approval does not match this payout")]
+    InvalidApproval,
+    #[msg("Batch exceeds the maximum number of winners")]
+    BatchTooLarge,
+    #[msg("Quest account listed more than once")]
+    DuplicateQuestAccount,
+    #[msg("Winner count would overflow")]
+    WinnerCountOverflow,
+    #[msg("Reward claims are paused")]
+    ClaimsPaused,
+    #[msg("Only the quest creator may redirect the remaining reward")]
+    UnauthorizedRecipient,
+    #[msg("Funds cannot be moved between these quests")]
+    InvalidQuestTransfer,
+    #[msg("Record is already in the current layout")]
+    AlreadyMigrated,
+    #[msg("Quest cannot move to that state from its current one")]
+    InvalidStateTransition,
+    #[msg("Payout would leave too little for the remaining winners' minimum reward")]
+    MinRewardInfeasible,
+    #[msg("Winner cannot be a program address")]
+    InvalidWinner,
+    #[msg("Reward records can only be closed once the quest is cancelled or completed")]
+    QuestStillOpen,
+    #[msg("Reward records of this quest were closed, so it cannot be reactivated")]
+    RewardRecordsClosed,
+    #[msg("Amount does not exceed the large reward threshold")]
+    BelowApprovalThreshold,
+    #[msg("Blocklist is full")]
+    BlocklistFull,
+    #[msg("The audit log must be passed once it is initialized")]
+    AuditLogRequired,
+    #[msg("Reward records do not add up to the quest's distributed total")]
+    IncompleteRewardRecords,
+}
+
+#[event]
+pub struct RewardSent {
+    pub quest: Pubkey,
+    pub winner: Pubkey,
+    pub amount: u64,
+    /// Undistributed quest funds after this payout
+    pub remaining_amount: u64,
+    /// Winner slots left after this payout
+    pub remaining_slots: u32,
+}
+
+#[event]
+pub struct RemainingRewardClaimed {
+    pub quest: Pubkey,
+    pub claimer: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+    /// False when the owner claimed on the creator's behalf
+    pub by_creator: bool,
+    /// Token account the remaining reward was paid to; default when burned
+    pub recipient: Pubkey,
+    pub burned: bool,
+}
+
+#[event]
+pub struct MaxWinnersOverridden {
+    pub quest: Pubkey,
+    pub owner: Pubkey,
+    pub old_max_winners: u32,
+    pub new_max_winners: u32,
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        init,
+        payer = owner,
+        space = GLOBAL_STATE_SPACE,
+        seeds = [GLOBAL_STATE_SEED],
+        bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    #[account(
+        constraint = program.programdata_address()? == Some(program_data.key())
+            @ CustomError::UnauthorizedInit
+    )]
+    pub program: Program<'info, program::SvmContracts>,
+    pub program_data: Account<'info, ProgramData>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(id: String)]
+pub struct CreateQuest<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED],
+        bump,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    pub token_mint: Account<'info, Mint>,
+    pub token_program: Program<'info, Token>,
+    /// CHECK: Created and initialized as a token account in the handler, after
+    /// checking nothing already lives at the derived address
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, quest.key().as_ref()],
+        bump,
+    )]
+    pub escrow_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        constraint = creator_token_account.mint == token_mint.key(),
+        constraint = creator_token_account.owner == creator.key()
+    )]
+    pub creator_token_account: Account<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = creator,
+        space = QUEST_SPACE
+    )]
+    pub quest: Account<'info, Quest>,
+    /// Reserves the id; init fails when another quest already holds it
+    #[account(
+        init,
+        payer = creator,
+        space = QUEST_ID_REGISTRY_SPACE,
+        seeds = [QUEST_ID_SEED, hash(id.as_bytes()).as_ref()],
+        bump
+    )]
+    pub quest_id_registry: Account<'info, QuestIdRegistry>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+#[instruction(new_id: String)]
+pub struct SplitQuest<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED],
+        bump,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    #[account(mut)]
+    pub quest: Account<'info, Quest>,
+    #[account(
+        mut,
+        constraint = escrow_account.key() == quest.escrow_account,
+        constraint = escrow_account.owner == global_state.key()
+    )]
+    pub escrow_account: Account<'info, TokenAccount>,
+    #[account(constraint = token_mint.key() == quest.token_mint)]
+    pub token_mint: Account<'info, Mint>,
+    #[account(
+        init,
+        payer = creator,
+        space = QUEST_SPACE
+    )]
+    pub new_quest: Account<'info, Quest>,
+    /// Reserves the new id; init fails when another quest already holds it
+    #[account(
+        init,
+        payer = creator,
+        space = QUEST_ID_REGISTRY_SPACE,
+        seeds = [QUEST_ID_SEED, hash(new_id.as_bytes()).as_ref()],
+        bump
+    )]
+    pub quest_id_registry: Account<'info, QuestIdRegistry>,
+    /// CHECK: Created and initialized as a token account in the handler
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, new_quest.key().as_ref()],
+        bump,
+    )]
+    pub new_escrow_account: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MergeQuests<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED],
+        bump,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    #[account(mut)]
+    pub quest: Account<'info, Quest>,
+    #[account(
+        mut,
+        constraint = escrow_account.key() == quest.escrow_account,
+        constraint = escrow_account.owner == global_state.key()
+    )]
+    pub escrow_account: Account<'info, TokenAccount>,
+    #[account(mut, close = creator)]
+    pub source_quest: Account<'info, Quest>,
+    #[account(
+        mut,
+        constraint = source_escrow_account.key() == source_quest.escrow_account,
+        constraint = source_escrow_account.owner == global_state.key()
+    )]
+    pub source_escrow_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct TransferBetweenQuests<'info> {
+    pub creator: Signer<'info>,
+    #[account(
+        seeds = [GLOBAL_STATE_SEED],
+        bump,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    #[account(mut)]
+    pub quest: Account<'info, Quest>,
+    #[account(
+        mut,
+        constraint = escrow_account.key() == quest.escrow_account,
+        constraint = escrow_account.owner == global_state.key()
+    )]
+    pub escrow_account: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub source_quest: Account<'info, Quest>,
+    #[account(
+        mut,
+        constraint = source_escrow_account.key() == source_quest.escrow_account,
+        constraint = source_escrow_account.owner == global_state.key()
+    )]
+    pub source_escrow_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct GetQuestInfo<'info> {
+    pub quest: Account<'info, Quest>,
+}
+
+#[derive(Accounts)]
+pub struct GetQuestContext<'info> {
+    #[account(
+        seeds = [GLOBAL_STATE_SEED],
+        bump,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    pub quest: Account<'info, Quest>,
+}
+
+#[derive(Accounts)]
+pub struct IsRemainingClaimable<'info> {
+    #[account(
+        seeds = [GLOBAL_STATE_SEED],
+        bump,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    pub quest: Account<'info, Quest>,
+    #[account(constraint = escrow_account.key() == quest.escrow_account)]
+    pub escrow_account: Account<'info, TokenAccount>,
+}
+
+#[derive(Accounts)]
+pub struct PreviewBatch<'info> {
+    #[account(
+        seeds = [GLOBAL_STATE_SEED],
+        bump,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    pub quest: Account<'info, Quest>,
+    #[account(constraint = escrow_account.key() == quest.escrow_account)]
+    pub escrow_account: Account<'info, TokenAccount>,
+}
+
+#[derive(Accounts)]
+pub struct GetEscrowShortfall<'info> {
+    pub quest: Account<'info, Quest>,
+    #[account(constraint = escrow_account.key() == quest.escrow_account)]
+    pub escrow_account: Account<'info, TokenAccount>,
+}
+
+#[derive(Accounts)]
+pub struct QuestHealthCheck<'info> {
+    pub quest: Account<'info, Quest>,
+    /// Deliberately unconstrained so a mismatched escrow is reported, not rejected
+    pub escrow_account: Account<'info, TokenAccount>,
+}
+
+#[derive(Accounts)]
+pub struct GetWinnerReward<'info> {
+    #[account(
+        seeds = [b"reward_claimed", reward_claimed.quest.as_ref(), reward_claimed.winner.as_ref()],
+        bump
+    )]
+    pub reward_claimed: Account<'info, RewardClaimed>,
+}
+
+#[derive(Accounts)]
+pub struct CheckWinnerClaimed<'info> {
+    /// CHECK: Only used as a key for the RewardClaimed PDAs
+    pub winner: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct EstimateQuestRent {}
+
+#[derive(Accounts)]
+pub struct ToBaseUnits<'info> {
+    pub token_mint: Account<'info, Mint>,
+}
+
+#[derive(Accounts)]
+pub struct GetAllQuests<'info> {
+    pub global_state: Account<'info, GlobalState>,
+}
+
+#[derive(Accounts)]
+pub struct CancelQuest<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED],
+        bump,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    #[account(mut)]
+    pub quest: Account<'info, Quest>,
+    #[account(
+        mut,
+        constraint = escrow_account.mint == quest.token_mint,
+        constraint = escrow_account.owner == global_state.key()
+    )]
+    pub escrow_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = creator_token_account.mint == quest.token_mint,
+        constraint = creator_token_account.owner == creator.key()
+    )]
+    pub creator_token_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ReactivateQuest<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    #[account(
+        seeds = [GLOBAL_STATE_SEED],
+        bump,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    #[account(mut)]
+    pub quest: Account<'info, Quest>,
+    #[account(
+        mut,
+        constraint = escrow_account.key() == quest.escrow_account,
+        constraint = escrow_account.owner == global_state.key()
+    )]
+    pub escrow_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = creator_token_account.mint == quest.token_mint,
+        constraint = creator_token_account.owner == creator.key()
+    )]
+    pub creator_token_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateQuestConfig<'info> {
+    pub creator: Signer<'info>,
+    #[account(mut)]
+    pub quest: Account<'info, Quest>,
+}
+
+#[derive(Accounts)]
+pub struct ReconcileQuest<'info> {
+    pub creator: Signer<'info>,
+    #[account(mut)]
+    pub quest: Account<'info, Quest>,
+    #[account(constraint = escrow_account.key() == quest.escrow_account)]
+    pub escrow_account: Account<'info, TokenAccount>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateQuestStatus<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED],
+        bump,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    #[account(mut)]
+    pub quest: Account<'info, Quest>,
+    /// Required once `initialize_audit_log` has run; only the singleton log PDA can hold this type
+    #[account(mut)]
+    pub audit_log: Option<Account<'info, AuditLog>>,
+}
+
+#[derive(Accounts)]
+pub struct ModifyToken<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED],
+        bump,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    pub token_mint: Account<'info, Mint>,
+    /// Required once `initialize_audit_log` has run; only the singleton log PDA can hold this type
+    #[account(mut)]
+    pub audit_log: Option<Account<'info, AuditLog>>,
 }
 
 #[derive(Accounts)]
-pub struct Initialize<'info> {
-    #[account(mut)]
+pub struct ModifyTokens<'info> {
     pub owner: Signer<'info>,
     #[account(
-        init,
-        payer = owner,
-        space = GLOBAL_STATE_SPACE,
+        mut,
         seeds = [GLOBAL_STATE_SEED],
-        bump
+        bump,
     )]
     pub global_state: Account<'info, GlobalState>,
-    pub system_program: Program<'info, System>,
+    /// Required once `initialize_audit_log` has run; only the singleton log PDA can hold this type
+    #[account(mut)]
+    pub audit_log: Option<Account<'info, AuditLog>>,
 }
 
 #[derive(Accounts)]
-pub struct CreateQuest<'info> {
-    #[account(mut)]
-    pub creator: Signer<'info>,
+pub struct PauseContract<'info> {
     #[account(mut)]
-    pub global_state: Account<'info, GlobalState>,
-    pub token_mint: Account<'info, Mint>,
-    pub token_program: Program<'info, Token>,
-    #[account(
-        init,
-        payer = creator,
-        seeds = [b"escrow", quest.key().as_ref()],
-        bump,
-        token::mint = token_mint,
-        token::authority = global_state,
-    )]
-    pub escrow_account: Account<'info, TokenAccount>,
+    pub owner: Signer<'info>,
     #[account(
         mut,
-        constraint = creator_token_account.mint == token_mint.key(),
-        constraint = creator_token_account.owner == creator.key()
+        seeds = [GLOBAL_STATE_SEED],
+        bump,
     )]
-    pub creator_token_account: Account<'info, TokenAccount>,
+    pub global_state: Account<'info, GlobalState>,
+    /// Required once `initialize_audit_log` has run; only the singleton log PDA can hold this type
+    #[account(mut)]
+    pub audit_log: Option<Account<'info, AuditLog>>,
+}
+
+#[derive(Accounts)]
+pub struct OwnerModerateQuest<'info> {
+    pub owner: Signer<'info>,
     #[account(
-        init,
-        payer = creator,
-        space = QUEST_SPACE
+        seeds = [GLOBAL_STATE_SEED],
+        bump,
     )]
+    pub global_state: Account<'info, GlobalState>,
+    #[account(mut)]
     pub quest: Account<'info, Quest>,
-    pub system_program: Program<'info, System>,
-    pub rent: Sysvar<'info, Rent>,
+    /// Required once `initialize_audit_log` has run; only the singleton log PDA can hold this type
+    #[account(mut)]
+    pub audit_log: Option<Account<'info, AuditLog>>,
 }
 
 #[derive(Accounts)]
-pub struct GetQuestInfo<'info> {
-    pub quest: Account<'info, Quest>,
+pub struct UpdateConfig<'info> {
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED],
+        bump,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    /// Required once `initialize_audit_log` has run; only the singleton log PDA can hold this type
+    #[account(mut)]
+    pub audit_log: Option<Account<'info, AuditLog>>,
 }
 
 #[derive(Accounts)]
-pub struct GetAllQuests<'info> {
+pub struct AddBlockedAddress<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED],
+        bump,
+        realloc = GLOBAL_STATE_SPACE + (global_state.blocklist.len() + 1) * PUBKEY_SIZE,
+        realloc::payer = owner,
+        realloc::zero = false,
+    )]
     pub global_state: Account<'info, GlobalState>,
+    pub system_program: Program<'info, System>,
+    /// Required once `initialize_audit_log` has run; only the singleton log PDA can hold this type
+    #[account(mut)]
+    pub audit_log: Option<Account<'info, AuditLog>>,
 }
 
 #[derive(Accounts)]
-pub struct CancelQuest<'info> {
+pub struct RemoveBlockedAddress<'info> {
     #[account(mut)]
-    pub creator: Signer<'info>,
+    pub owner: Signer<'info>,
     #[account(
         mut,
         seeds = [GLOBAL_STATE_SEED],
         bump,
+        realloc = GLOBAL_STATE_SPACE + global_state.blocklist.len().saturating_sub(1) * PUBKEY_SIZE,
+        realloc::payer = owner,
+        realloc::zero = false,
     )]
     pub global_state: Account<'info, GlobalState>,
+    pub system_program: Program<'info, System>,
+    /// Required once `initialize_audit_log` has run; only the singleton log PDA can hold this type
     #[account(mut)]
-    pub quest: Account<'info, Quest>,
+    pub audit_log: Option<Account<'info, AuditLog>>,
+}
+
+#[derive(Accounts)]
+pub struct CheckAuthorization<'info> {
+    pub signer: Signer<'info>,
     #[account(
-        mut,
-        constraint = escrow_account.mint == quest.token_mint,
-        constraint = escrow_account.owner == global_state.key()
+        seeds = [GLOBAL_STATE_SEED],
+        bump,
     )]
-    pub escrow_account: Account<'info, TokenAccount>,
+    pub global_state: Account<'info, GlobalState>,
+}
+
+#[derive(Accounts)]
+pub struct GetConfig<'info> {
     #[account(
-        mut,
-        constraint = creator_token_account.mint == quest.token_mint,
-        constraint = creator_token_account.owner == creator.key()
+        seeds = [GLOBAL_STATE_SEED],
+        bump,
     )]
-    pub creator_token_account: Account<'info, TokenAccount>,
-    pub token_program: Program<'info, Token>,
+    pub global_state: Account<'info, GlobalState>,
 }
 
 #[derive(Accounts)]
-pub struct UpdateQuestStatus<'info> {
-    #[account(mut)]
-    pub owner: Signer<'info>,
+pub struct ProposeLargeReward<'info> {
     #[account(mut)]
+    pub approver: Signer<'info>,
+    #[account(
+        seeds = [GLOBAL_STATE_SEED],
+        bump,
+    )]
     pub global_state: Account<'info, GlobalState>,
-    #[account(mut)]
     pub quest: Account<'info, Quest>,
+    /// CHECK: Only used as a key for the approval PDA
+    pub winner: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = approver,
+        space = REWARD_APPROVAL_SPACE,
+        seeds = [REWARD_APPROVAL_SEED, quest.key().as_ref(), winner.key().as_ref()],
+        bump
+    )]
+    pub reward_approval: Account<'info, RewardApproval>,
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct ModifyToken<'info> {
-    #[account(mut)]
-    pub owner: Signer<'info>,
-    #[account(mut)]
+pub struct ApproveLargeReward<'info> {
+    pub approver: Signer<'info>,
+    #[account(
+        seeds = [GLOBAL_STATE_SEED],
+        bump,
+    )]
     pub global_state: Account<'info, GlobalState>,
-    pub token_mint: Account<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [REWARD_APPROVAL_SEED, reward_approval.quest.as_ref(), reward_approval.winner.as_ref()],
+        bump
+    )]
+    pub reward_approval: Account<'info, RewardApproval>,
 }
 
 #[derive(Accounts)]
-pub struct PauseContract<'info> {
-    #[account(mut)]
-    pub owner: Signer<'info>,
+pub struct CancelLargeReward<'info> {
     #[account(mut)]
+    pub canceller: Signer<'info>,
+    #[account(
+        seeds = [GLOBAL_STATE_SEED],
+        bump,
+    )]
     pub global_state: Account<'info, GlobalState>,
+    #[account(
+        mut,
+        seeds = [REWARD_APPROVAL_SEED, reward_approval.quest.as_ref(), reward_approval.winner.as_ref()],
+        bump,
+        close = proposer
+    )]
+    pub reward_approval: Account<'info, RewardApproval>,
+    /// CHECK: Receives the rent back, whoever cancels
+    #[account(mut, address = reward_approval.proposer @ CustomError::InvalidRentRecipient)]
+    pub proposer: UncheckedAccount<'info>,
+    /// Required for the owner once `initialize_audit_log` has run; only the singleton log PDA can hold this type
+    #[account(mut)]
+    pub audit_log: Option<Account<'info, AuditLog>>,
 }
 
 #[derive(Accounts)]
@@ -488,9 +3616,12 @@ pub struct SendReward<'info> {
         constraint = winner_token_account.owner == winner.key()
     )]
     pub winner_token_account: Account<'info, TokenAccount>,
+    /// Funds the RewardClaimed rent; pass the owner for owner-funded sends
+    #[account(mut)]
+    pub payer: Signer<'info>,
     #[account(
         init,
-        payer = owner,
+        payer = payer,
         space = REWARD_CLAIMED_SPACE,
         seeds = [b"reward_claimed", quest.key().as_ref(), winner.key().as_ref()],
         bump
@@ -498,6 +3629,70 @@ pub struct SendReward<'info> {
     pub reward_claimed: Account<'info, RewardClaimed>,
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
+    /// Required once `initialize_audit_log` has run; only the singleton log PDA can hold this type
+    #[account(mut)]
+    pub audit_log: Option<Account<'info, AuditLog>>,
+    /// Required for payouts above the large reward threshold
+    #[account(mut)]
+    pub reward_approval: Option<Account<'info, RewardApproval>>,
+    /// CHECK: Receives the approval's rent; checked against its proposer in the handler
+    #[account(mut)]
+    pub proposer: Option<UncheckedAccount<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct SendRewardBatch<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED],
+        bump,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    #[account(mut)]
+    pub quest: Account<'info, Quest>,
+    #[account(
+        mut,
+        constraint = escrow_account.mint == quest.token_mint,
+        constraint = escrow_account.owner == global_state.key()
+    )]
+    pub escrow_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    /// Required once `initialize_audit_log` has run; only the singleton log PDA can hold this type
+    #[account(mut)]
+    pub audit_log: Option<Account<'info, AuditLog>>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeAuditLog<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED],
+        bump,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    #[account(
+        init,
+        payer = owner,
+        space = AUDIT_LOG_SPACE,
+        seeds = [AUDIT_LOG_SEED],
+        bump,
+    )]
+    pub audit_log: Account<'info, AuditLog>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct GetAuditLog<'info> {
+    #[account(
+        seeds = [AUDIT_LOG_SEED],
+        bump,
+    )]
+    pub audit_log: Account<'info, AuditLog>,
 }
 
 #[derive(Accounts)]
@@ -510,6 +3705,9 @@ pub struct SetOwner<'info> {
         bump,
     )]
     pub global_state: Account<'info, GlobalState>,
+    /// Required once `initialize_audit_log` has run; only the singleton log PDA can hold this type
+    #[account(mut)]
+    pub audit_log: Option<Account<'info, AuditLog>>,
 }
 
 #[derive(Accounts)]
@@ -536,5 +3734,121 @@ pub struct ClaimRemainingReward<'info> {
         constraint = creator_token_account.owner == quest.creator
     )]
     pub creator_token_account: Account<'info, TokenAccount>,
+    /// Creator-chosen destination; defaults to `creator_token_account`
+    #[account(
+        mut,
+        constraint = recipient_token_account.mint == quest.token_mint @ CustomError::InvalidTokenMint
+    )]
+    pub recipient_token_account: Option<Account<'info, TokenAccount>>,
+    /// Required when the quest burns its leftover funds
+    #[account(
+        mut,
+        constraint = token_mint.key() == quest.token_mint @ CustomError::InvalidTokenMint
+    )]
+    pub token_mint: Option<Account<'info, Mint>>,
     pub token_program: Program<'info, Token>,
+    /// Required for the owner once `initialize_audit_log` has run; only the singleton log PDA can hold this type
+    #[account(mut)]
+    pub audit_log: Option<Account<'info, AuditLog>>,
+}
+
+#[derive(Accounts)]
+pub struct CloseCancelledRewardClaimed<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        seeds = [GLOBAL_STATE_SEED],
+        bump,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    #[account(mut)]
+    pub quest: Account<'info, Quest>,
+    /// Required once `initialize_audit_log` has run; only the singleton log PDA can hold this type
+    #[account(mut)]
+    pub audit_log: Option<Account<'info, AuditLog>>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateRewardClaimed<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        seeds = [GLOBAL_STATE_SEED],
+        bump,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    /// CHECK: May hold a pre-versioning layout that no longer deserializes; the
+    /// discriminator and PDA are verified in the handler
+    #[account(mut, owner = crate::ID)]
+    pub reward_claimed: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+    /// Required once `initialize_audit_log` has run; only the singleton log PDA can hold this type
+    #[account(mut)]
+    pub audit_log: Option<Account<'info, AuditLog>>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateGlobalState<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    /// CHECK: May hold an older layout that no longer deserializes; it is read
+    /// and its owner checked in the handler
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED],
+        bump,
+        owner = crate::ID,
+    )]
+    pub global_state: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+    /// Required once `initialize_audit_log` has run; only the singleton log PDA can hold this type
+    #[account(mut)]
+    pub audit_log: Option<Account<'info, AuditLog>>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateQuest<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        seeds = [GLOBAL_STATE_SEED],
+        bump,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    /// CHECK: May hold an older layout that no longer deserializes; the
+    /// discriminator is verified in the handler
+    #[account(mut, owner = crate::ID)]
+    pub quest: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+    /// Required once `initialize_audit_log` has run; only the singleton log PDA can hold this type
+    #[account(mut)]
+    pub audit_log: Option<Account<'info, AuditLog>>,
+}
+
+#[derive(Accounts)]
+pub struct CloseRewardClaimed<'info> {
+    #[account(mut)]
+    pub closer: Signer<'info>,
+    #[account(
+        seeds = [GLOBAL_STATE_SEED],
+        bump,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    #[account(
+        mut,
+        seeds = [b"reward_claimed", reward_claimed.quest.as_ref(), reward_claimed.winner.as_ref()],
+        bump
+    )]
+    pub reward_claimed: Account<'info, RewardClaimed>,
+    #[account(
+        mut,
+        constraint = quest.key() == reward_claimed.quest @ CustomError::InvalidRewardClaimedAccount
+    )]
+    pub quest: Account<'info, Quest>,
+    /// CHECK: Only receives lamports; routing rules are enforced in the handler
+    #[account(mut)]
+    pub recipient: Option<UncheckedAccount<'info>>,
+    /// Required for the owner once `initialize_audit_log` has run; only the singleton log PDA can hold this type
+    #[account(mut)]
+    pub audit_log: Option<Account<'info, AuditLog>>,
 }
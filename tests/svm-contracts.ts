@@ -3,18 +3,24 @@ import { Program } from "@coral-xyz/anchor";
 import {
   createAssociatedTokenAccountInstruction,
   createMint,
+  freezeAccount,
   getAccount,
+  getMint,
   getAssociatedTokenAddress,
   mintTo,
   TOKEN_PROGRAM_ID,
+  transfer,
 } from "@solana/spl-token";
 import {
+  ComputeBudgetProgram,
   Keypair,
   PublicKey,
   SystemProgram,
   Transaction,
 } from "@solana/web3.js";
 import { expect } from "chai";
+import { createHash } from "crypto";
+import { readFileSync } from "fs";
 import { SvmContracts } from "../target/types/svm_contracts";
 
 describe("svm-contracts", () => {
@@ -23,7 +29,13 @@ describe("svm-contracts", () => {
   anchor.setProvider(provider);
 
   const program = anchor.workspace.svmContracts as Program<SvmContracts>;
-  const owner = Keypair.generate();
+  // initialize is restricted to the upgrade authority, which for a local
+  // deploy is the provider wallet
+  const owner = (provider.wallet as anchor.Wallet).payer;
+  const [programDataPDA] = PublicKey.findProgramAddressSync(
+    [program.programId.toBuffer()],
+    new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111")
+  );
   let globalStatePDA: PublicKey;
   let supportedTokenMint: Keypair;
 
@@ -42,7 +54,175 @@ describe("svm-contracts", () => {
     await provider.connection.confirmTransaction(signature);
   });
 
+  const escrowPDAFor = (quest: PublicKey): PublicKey =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow"), quest.toBuffer()],
+      program.programId
+    )[0];
+
+  const rewardClaimedPDAFor = (quest: PublicKey, winner: PublicKey): PublicKey =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("reward_claimed"), quest.toBuffer(), winner.toBuffer()],
+      program.programId
+    )[0];
+
+  // Quest ids are reserved under the sha256 of the id
+  const questIdRegistryPDAFor = (id: string): PublicKey =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("quest_id"), createHash("sha256").update(id).digest()],
+      program.programId
+    )[0];
+
+  // Creates a quest funded from the owner's ATA of the supported mint
+  const createTestQuest = async (
+    id: string,
+    amount: anchor.BN,
+    deadline: anchor.BN,
+    maxWinners: number
+  ): Promise<{ quest: PublicKey; escrow: PublicKey }> => {
+    const questKeypair = Keypair.generate();
+    const escrow = escrowPDAFor(questKeypair.publicKey);
+    const creatorTokenAccount = await getAssociatedTokenAddress(
+      supportedTokenMint.publicKey,
+      owner.publicKey
+    );
+
+    await program.methods
+      .createQuest(id, amount, deadline, maxWinners)
+      .accounts({
+        creator: owner.publicKey,
+        globalState: globalStatePDA,
+        tokenMint: supportedTokenMint.publicKey,
+        escrowAccount: escrow,
+        creatorTokenAccount: creatorTokenAccount,
+        quest: questKeypair.publicKey,
+        questIdRegistry: questIdRegistryPDAFor(id),
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .signers([owner, questKeypair])
+      .rpc();
+
+    return { quest: questKeypair.publicKey, escrow };
+  };
+
+  // Generates a funded winner with an ATA for the supported mint
+  const createTestWinner = async (): Promise<{
+    winner: Keypair;
+    winnerTokenAccount: PublicKey;
+  }> => {
+    const winner = Keypair.generate();
+    const signature = await provider.connection.requestAirdrop(
+      winner.publicKey,
+      2 * anchor.web3.LAMPORTS_PER_SOL
+    );
+    await provider.connection.confirmTransaction(signature);
+
+    const winnerTokenAccount = await getAssociatedTokenAddress(
+      supportedTokenMint.publicKey,
+      winner.publicKey
+    );
+    const transaction = new Transaction().add(
+      createAssociatedTokenAccountInstruction(
+        winner.publicKey,
+        winnerTokenAccount,
+        winner.publicKey,
+        supportedTokenMint.publicKey
+      )
+    );
+    await provider.sendAndConfirm(transaction, [winner]);
+
+    return { winner, winnerTokenAccount };
+  };
+
+  const sendTestReward = async (
+    quest: PublicKey,
+    escrow: PublicKey,
+    winner: PublicKey,
+    winnerTokenAccount: PublicKey,
+    rewardAmount: anchor.BN
+  ) =>
+    program.methods
+      .sendReward(rewardAmount)
+      .accounts({
+        owner: owner.publicKey,
+        payer: owner.publicKey,
+        globalState: globalStatePDA,
+        quest,
+        escrowAccount: escrow,
+        winner,
+        winnerTokenAccount,
+        rewardClaimed: rewardClaimedPDAFor(quest, winner),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([owner])
+      .rpc();
+
+  // Decodes the program events emitted by a confirmed transaction
+  const eventsFromTx = async (signature: string) => {
+    const tx = await provider.connection.getTransaction(signature, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    const parser = new anchor.EventParser(
+      program.programId,
+      new anchor.BorshCoder(program.idl)
+    );
+    return [...parser.parseLogs(tx.meta.logMessages)];
+  };
+
   describe("initialize", () => {
+    it("should reject an owner that is not the upgrade authority", async () => {
+      const impostor = Keypair.generate();
+      const signature = await provider.connection.requestAirdrop(
+        impostor.publicKey,
+        anchor.web3.LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(signature);
+
+      try {
+        await program.methods
+          .initialize([Keypair.generate().publicKey])
+          .accounts({
+            owner: impostor.publicKey,
+            globalState: globalStatePDA,
+            program: program.programId,
+            programData: programDataPDA,
+            system_program: SystemProgram.programId,
+          })
+          .signers([impostor])
+          .rpc();
+        expect.fail("Expected the transaction to fail");
+      } catch (error) {
+        expect(error.error.errorCode.code).to.equal("UnauthorizedInit");
+      }
+    });
+
+    it("should reject a supported mint that is not a real mint", async () => {
+      const bogusMint = Keypair.generate().publicKey;
+      try {
+        await program.methods
+          .initialize([bogusMint])
+          .accounts({
+            owner: owner.publicKey,
+            globalState: globalStatePDA,
+            program: program.programId,
+            programData: programDataPDA,
+            system_program: SystemProgram.programId,
+          })
+          .remainingAccounts([
+            { pubkey: bogusMint, isWritable: false, isSigner: false },
+          ])
+          .signers([owner])
+          .rpc();
+        expect.fail("Expected the transaction to fail");
+      } catch (error) {
+        expect(error.error.errorCode.code).to.equal("InvalidTokenMint");
+      }
+    });
+
     it("should initialize global state with supported token mints", async () => {
       // Create token mint for testing
       supportedTokenMint = Keypair.generate();
@@ -70,8 +250,17 @@ describe("svm-contracts", () => {
         .accounts({
           owner: owner.publicKey,
           globalState: globalStatePDA,
+          program: program.programId,
+          programData: programDataPDA,
           system_program: SystemProgram.programId,
         })
+        .remainingAccounts(
+          supportedTokenMints.map((pubkey) => ({
+            pubkey,
+            isWritable: false,
+            isSigner: false,
+          }))
+        )
         .signers([owner])
         .rpc();
 
@@ -94,6 +283,8 @@ describe("svm-contracts", () => {
           .accounts({
             owner: owner.publicKey,
             globalState: globalStatePDA,
+            program: program.programId,
+            programData: programDataPDA,
             system_program: SystemProgram.programId,
           })
           .signers([owner])
@@ -114,6 +305,8 @@ describe("svm-contracts", () => {
           .accounts({
             owner: nonSigner.publicKey,
             globalState: globalStatePDA,
+            program: program.programId,
+            programData: programDataPDA,
             system_program: SystemProgram.programId,
           })
           .signers([]) // Deliberately omitting the owner signer
@@ -150,6 +343,8 @@ describe("svm-contracts", () => {
           .accounts({
             owner: owner.publicKey,
             globalState: globalStatePDA,
+            program: program.programId,
+            programData: programDataPDA,
             system_program: SystemProgram.programId,
           })
           .signers([owner])
@@ -305,6 +500,8 @@ describe("svm-contracts", () => {
           .accounts({
             owner: owner.publicKey,
             globalState: globalStatePDA,
+            program: program.programId,
+            programData: programDataPDA,
             system_program: SystemProgram.programId,
           })
           .signers([owner])
@@ -389,6 +586,7 @@ describe("svm-contracts", () => {
           escrowAccount: escrowPDA,
           creatorTokenAccount: creatorTokenAccount,
           quest: questKeypair.publicKey,
+          questIdRegistry: questIdRegistryPDAFor(questId),
           systemProgram: SystemProgram.programId,
           tokenProgram: TOKEN_PROGRAM_ID,
           rent: anchor.web3.SYSVAR_RENT_PUBKEY,
@@ -475,6 +673,7 @@ describe("svm-contracts", () => {
             tokenMint: unsupportedMint.publicKey,
             escrowAccount: escrowPDA,
             quest: newQuestKeypair.publicKey,
+            questIdRegistry: questIdRegistryPDAFor(questId),
             systemProgram: SystemProgram.programId,
             tokenProgram: TOKEN_PROGRAM_ID,
           })
@@ -562,19 +761,25 @@ describe("svm-contracts", () => {
       }
     });
 
-    it("should allow owner to update quest status", async () => {
-      await program.methods
-        .updateQuestStatus(true)
-        .accounts({
-          owner: owner.publicKey,
-          globalState: globalStatePDA,
-          quest: questKeypair.publicKey,
-        })
-        .signers([owner])
-        .rpc();
+    it("should not let the owner reactivate a cancelled quest through its status", async () => {
+      try {
+        await program.methods
+          .updateQuestStatus(true)
+          .accounts({
+            owner: owner.publicKey,
+            globalState: globalStatePDA,
+            quest: questKeypair.publicKey,
+          })
+          .signers([owner])
+          .rpc();
+        expect.fail("Expected the transaction to fail");
+      } catch (error) {
+        expect(error.error.errorCode.code).to.equal("InvalidStateTransition");
+      }
 
       const quest = await program.account.quest.fetch(questKeypair.publicKey);
-      expect(quest.isActive).to.be.true;
+      expect(quest.isActive).to.be.false;
+      expect(quest.cancelled).to.be.true;
     });
 
     it("should not allow non-owner to update quest status", async () => {
@@ -661,6 +866,7 @@ describe("svm-contracts", () => {
             escrowAccount: escrowPDA,
             creatorTokenAccount: creatorTokenAccount,
             quest: questKeypair.publicKey,
+            questIdRegistry: questIdRegistryPDAFor("reward-test-quest"),
             systemProgram: SystemProgram.programId,
             tokenProgram: TOKEN_PROGRAM_ID,
             rent: anchor.web3.SYSVAR_RENT_PUBKEY,
@@ -691,6 +897,7 @@ describe("svm-contracts", () => {
           .sendReward(rewardAmount)
           .accounts({
             owner: owner.publicKey,
+            payer: owner.publicKey,
             globalState: globalStatePDA,
             quest: questKeypair.publicKey,
             escrowAccount: escrowPDA,
@@ -766,6 +973,7 @@ describe("svm-contracts", () => {
             .sendReward(rewardAmount)
             .accounts({
               owner: owner.publicKey,
+              payer: owner.publicKey,
               globalState: globalStatePDA,
               quest: questKeypair.publicKey,
               escrowAccount: escrowPDA,
@@ -816,6 +1024,7 @@ describe("svm-contracts", () => {
             .sendReward(rewardAmount)
             .accounts({
               owner: nonOwner.publicKey,
+              payer: nonOwner.publicKey,
               globalState: globalStatePDA,
               quest: questKeypair.publicKey,
               escrowAccount: escrowPDA,
@@ -848,6 +1057,7 @@ describe("svm-contracts", () => {
             .sendReward(rewardAmount)
             .accounts({
               owner: owner.publicKey,
+              payer: owner.publicKey,
               globalState: globalStatePDA,
               quest: questKeypair.publicKey,
               escrowAccount: escrowPDA,
@@ -892,6 +1102,7 @@ describe("svm-contracts", () => {
             .sendReward(rewardAmount)
             .accounts({
               owner: owner.publicKey,
+              payer: owner.publicKey,
               globalState: globalStatePDA,
               quest: questKeypair.publicKey,
               escrowAccount: escrowPDA,
@@ -970,6 +1181,7 @@ describe("svm-contracts", () => {
             escrowAccount: claimEscrowPDA,
             creatorTokenAccount: claimCreatorTokenAccount,
             quest: claimQuestKeypair.publicKey,
+            questIdRegistry: questIdRegistryPDAFor("claim-test-quest"),
             systemProgram: SystemProgram.programId,
             tokenProgram: TOKEN_PROGRAM_ID,
             rent: anchor.web3.SYSVAR_RENT_PUBKEY,
@@ -1103,6 +1315,7 @@ describe("svm-contracts", () => {
             escrowAccount: adminEscrowPDA,
             creatorTokenAccount: adminCreatorTokenAccount,
             quest: adminQuestKeypair.publicKey,
+            questIdRegistry: questIdRegistryPDAFor("admin-claim-test"),
             systemProgram: SystemProgram.programId,
             tokenProgram: TOKEN_PROGRAM_ID,
             rent: anchor.web3.SYSVAR_RENT_PUBKEY,
@@ -1219,6 +1432,7 @@ describe("svm-contracts", () => {
             escrowAccount: activeEscrowPDA,
             creatorTokenAccount: activeCreatorTokenAccount,
             quest: activeQuestKeypair.publicKey,
+            questIdRegistry: questIdRegistryPDAFor("active-quest-test"),
             systemProgram: SystemProgram.programId,
             tokenProgram: TOKEN_PROGRAM_ID,
             rent: anchor.web3.SYSVAR_RENT_PUBKEY,
@@ -1296,6 +1510,7 @@ describe("svm-contracts", () => {
             escrowAccount: emptyEscrowPDA,
             creatorTokenAccount: emptyCreatorTokenAccount,
             quest: emptyQuestKeypair.publicKey,
+            questIdRegistry: questIdRegistryPDAFor("empty-quest-test"),
             systemProgram: SystemProgram.programId,
             tokenProgram: TOKEN_PROGRAM_ID,
             rent: anchor.web3.SYSVAR_RENT_PUBKEY,
@@ -1347,6 +1562,7 @@ describe("svm-contracts", () => {
           .sendReward(emptyAmount)
           .accounts({
             owner: owner.publicKey,
+            payer: owner.publicKey,
             globalState: globalStatePDA,
             quest: emptyQuestKeypair.publicKey,
             escrowAccount: emptyEscrowPDA,
@@ -1430,5 +1646,5512 @@ describe("svm-contracts", () => {
           .rpc();
       });
     });
+
+    describe("close reward claimed", () => {
+      let closeQuest: PublicKey;
+      let closeEscrow: PublicKey;
+      const closeReward = new anchor.BN(100000);
+      const paid: { winner: Keypair; winnerTokenAccount: PublicKey }[] = [];
+
+      before(async () => {
+        ({ quest: closeQuest, escrow: closeEscrow } = await createTestQuest(
+          "close-reward-quest",
+          new anchor.BN(1000000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          5
+        ));
+        for (let i = 0; i < 3; i++) {
+          const { winner, winnerTokenAccount } = await createTestWinner();
+          await sendTestReward(
+            closeQuest,
+            closeEscrow,
+            winner.publicKey,
+            winnerTokenAccount,
+            closeReward
+          );
+          paid.push({ winner, winnerTokenAccount });
+        }
+      });
+
+      it("should not close a record while the quest is still open", async () => {
+        const { winner } = paid[0];
+        try {
+          await program.methods
+            .closeRewardClaimed()
+            .accounts({
+              closer: winner.publicKey,
+              globalState: globalStatePDA,
+              rewardClaimed: rewardClaimedPDAFor(closeQuest, winner.publicKey),
+              quest: closeQuest,
+              recipient: null,
+            })
+            .signers([winner])
+            .rpc();
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("QuestStillOpen");
+        }
+
+        await program.methods
+          .cancelQuest()
+          .accounts({
+            creator: owner.publicKey,
+            globalState: globalStatePDA,
+            quest: closeQuest,
+            escrowAccount: closeEscrow,
+            creatorTokenAccount: await getAssociatedTokenAddress(
+              supportedTokenMint.publicKey,
+              owner.publicKey
+            ),
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .signers([owner])
+          .rpc();
+      });
+
+      it("should return rent to the winner when the winner closes", async () => {
+        const { winner } = paid[0];
+        const rewardClaimedPDA = rewardClaimedPDAFor(
+          closeQuest,
+          winner.publicKey
+        );
+
+        // Routing rent to anyone else is rejected
+        try {
+          await program.methods
+            .closeRewardClaimed()
+            .accounts({
+              closer: winner.publicKey,
+              globalState: globalStatePDA,
+              rewardClaimed: rewardClaimedPDA,
+              quest: closeQuest,
+              recipient: owner.publicKey,
+            })
+            .signers([winner])
+            .rpc();
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("InvalidRentRecipient");
+        }
+
+        const rent = await provider.connection.getBalance(rewardClaimedPDA);
+        const winnerBalanceBefore = await provider.connection.getBalance(
+          winner.publicKey
+        );
+
+        await program.methods
+          .closeRewardClaimed()
+          .accounts({
+            closer: winner.publicKey,
+            globalState: globalStatePDA,
+            rewardClaimed: rewardClaimedPDA,
+            quest: closeQuest,
+            recipient: null,
+          })
+          .signers([winner])
+          .rpc();
+
+        const winnerBalanceAfter = await provider.connection.getBalance(
+          winner.publicKey
+        );
+        // Winner pays the tx fee, so allow for it
+        expect(winnerBalanceAfter).to.be.greaterThan(
+          winnerBalanceBefore + rent - 10000
+        );
+        expect(await provider.connection.getAccountInfo(rewardClaimedPDA)).to.be
+          .null;
+      });
+
+      it("should let the owner route rent to an arbitrary recipient", async () => {
+        const { winner } = paid[1];
+        const rewardClaimedPDA = rewardClaimedPDAFor(
+          closeQuest,
+          winner.publicKey
+        );
+        const recipient = Keypair.generate().publicKey;
+        const rent = await provider.connection.getBalance(rewardClaimedPDA);
+
+        await program.methods
+          .closeRewardClaimed()
+          .accounts({
+            closer: owner.publicKey,
+            globalState: globalStatePDA,
+            rewardClaimed: rewardClaimedPDA,
+            quest: closeQuest,
+            recipient,
+          })
+          .signers([owner])
+          .rpc();
+
+        expect(await provider.connection.getBalance(recipient)).to.equal(rent);
+      });
+
+      it("should not allow a third party to close a reward record", async () => {
+        const { winner } = paid[2];
+        const { winner: stranger } = await createTestWinner();
+
+        try {
+          await program.methods
+            .closeRewardClaimed()
+            .accounts({
+              closer: stranger.publicKey,
+              globalState: globalStatePDA,
+              rewardClaimed: rewardClaimedPDAFor(closeQuest, winner.publicKey),
+              quest: closeQuest,
+              recipient: stranger.publicKey,
+            })
+            .signers([stranger])
+            .rpc();
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("UnauthorizedClose");
+        }
+      });
+    });
+
+    describe("frozen winner account", () => {
+      it("should reject sending reward to a frozen token account", async () => {
+        // Mint with a freeze authority so the winner ATA can be frozen
+        const freezableMint = Keypair.generate();
+        await createMint(
+          provider.connection,
+          owner,
+          owner.publicKey,
+          owner.publicKey,
+          9,
+          freezableMint
+        );
+        await program.methods
+          .addSupportedToken()
+          .accounts({
+            owner: owner.publicKey,
+            globalState: globalStatePDA,
+            tokenMint: freezableMint.publicKey,
+          })
+          .signers([owner])
+          .rpc();
+
+        const creatorAta = await getAssociatedTokenAddress(
+          freezableMint.publicKey,
+          owner.publicKey
+        );
+        const winner = Keypair.generate();
+        const winnerAta = await getAssociatedTokenAddress(
+          freezableMint.publicKey,
+          winner.publicKey
+        );
+        await provider.sendAndConfirm(
+          new Transaction().add(
+            createAssociatedTokenAccountInstruction(
+              owner.publicKey,
+              creatorAta,
+              owner.publicKey,
+              freezableMint.publicKey
+            ),
+            createAssociatedTokenAccountInstruction(
+              owner.publicKey,
+              winnerAta,
+              winner.publicKey,
+              freezableMint.publicKey
+            )
+          ),
+          [owner]
+        );
+        await mintTo(
+          provider.connection,
+          owner,
+          freezableMint.publicKey,
+          creatorAta,
+          owner,
+          1000000
+        );
+
+        const questKeypair = Keypair.generate();
+        const escrow = escrowPDAFor(questKeypair.publicKey);
+        await program.methods
+          .createQuest(
+            "frozen-winner-quest",
+            new anchor.BN(1000000),
+            new anchor.BN(Date.now() / 1000 + 86400),
+            5
+          )
+          .accounts({
+            creator: owner.publicKey,
+            globalState: globalStatePDA,
+            tokenMint: freezableMint.publicKey,
+            escrowAccount: escrow,
+            creatorTokenAccount: creatorAta,
+            quest: questKeypair.publicKey,
+            questIdRegistry: questIdRegistryPDAFor("frozen-winner-quest"),
+            systemProgram: SystemProgram.programId,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+          })
+          .signers([owner, questKeypair])
+          .rpc();
+
+        await freezeAccount(
+          provider.connection,
+          owner,
+          winnerAta,
+          freezableMint.publicKey,
+          owner
+        );
+
+        try {
+          await sendTestReward(
+            questKeypair.publicKey,
+            escrow,
+            winner.publicKey,
+            winnerAta,
+            new anchor.BN(100000)
+          );
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("FrozenAccount");
+        }
+      });
+    });
+
+    describe("per-quest pause", () => {
+      it("should allow owner to pause two quests in one call", async () => {
+        const deadline = new anchor.BN(Date.now() / 1000 + 86400);
+        const first = await createTestQuest(
+          "bulk-pause-1",
+          new anchor.BN(500000),
+          deadline,
+          3
+        );
+        const second = await createTestQuest(
+          "bulk-pause-2",
+          new anchor.BN(500000),
+          deadline,
+          3
+        );
+        const questAccounts = [first.quest, second.quest].map((pubkey) => ({
+          pubkey,
+          isWritable: true,
+          isSigner: false,
+        }));
+
+        await program.methods
+          .pauseQuests()
+          .accounts({
+            owner: owner.publicKey,
+            globalState: globalStatePDA,
+          })
+          .remainingAccounts(questAccounts)
+          .signers([owner])
+          .rpc();
+
+        for (const quest of [first.quest, second.quest]) {
+          expect((await program.account.quest.fetch(quest)).paused).to.be.true;
+        }
+
+        const { winner, winnerTokenAccount } = await createTestWinner();
+        try {
+          await sendTestReward(
+            first.quest,
+            first.escrow,
+            winner.publicKey,
+            winnerTokenAccount,
+            new anchor.BN(100000)
+          );
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("QuestPaused");
+        }
+
+        await program.methods
+          .unpauseQuests()
+          .accounts({
+            owner: owner.publicKey,
+            globalState: globalStatePDA,
+          })
+          .remainingAccounts(questAccounts)
+          .signers([owner])
+          .rpc();
+
+        for (const quest of [first.quest, second.quest]) {
+          expect((await program.account.quest.fetch(quest)).paused).to.be.false;
+        }
+      });
+
+      it("should not allow non-owner to pause quests", async () => {
+        const { winner: nonOwner } = await createTestWinner();
+        const { quest } = await createTestQuest(
+          "bulk-pause-3",
+          new anchor.BN(500000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        );
+
+        try {
+          await program.methods
+            .pauseQuests()
+            .accounts({
+              owner: nonOwner.publicKey,
+              globalState: globalStatePDA,
+            })
+            .remainingAccounts([
+              { pubkey: quest, isWritable: true, isSigner: false },
+            ])
+            .signers([nonOwner])
+            .rpc();
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error).to.exist;
+        }
+      });
+    });
+
+    describe("reward reserve", () => {
+      const setReserve = (quest: PublicKey, reserve: anchor.BN) =>
+        program.methods
+          .setReserveAmount(reserve)
+          .accounts({ creator: owner.publicKey, quest })
+          .signers([owner])
+          .rpc();
+
+      it("should keep the reserve locked before the deadline", async () => {
+        const { quest, escrow } = await createTestQuest(
+          "reserve-pre-deadline",
+          new anchor.BN(1000000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          5
+        );
+        await setReserve(quest, new anchor.BN(400000));
+
+        const { winner, winnerTokenAccount } = await createTestWinner();
+        try {
+          await sendTestReward(
+            quest,
+            escrow,
+            winner.publicKey,
+            winnerTokenAccount,
+            new anchor.BN(700000)
+          );
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("ReserveLocked");
+        }
+
+        // Up to amount - reserve is still distributable
+        await sendTestReward(
+          quest,
+          escrow,
+          winner.publicKey,
+          winnerTokenAccount,
+          new anchor.BN(600000)
+        );
+        const questAccount = await program.account.quest.fetch(quest);
+        expect(questAccount.totalRewardDistributed.toString()).to.equal(
+          "600000"
+        );
+      });
+
+      it("should release the reserve after the deadline", async () => {
+        const { quest, escrow } = await createTestQuest(
+          "reserve-post-deadline",
+          new anchor.BN(1000000),
+          new anchor.BN(Date.now() / 1000 - 60),
+          5
+        );
+        await setReserve(quest, new anchor.BN(400000));
+
+        const { winner, winnerTokenAccount } = await createTestWinner();
+        await sendTestReward(
+          quest,
+          escrow,
+          winner.publicKey,
+          winnerTokenAccount,
+          new anchor.BN(900000)
+        );
+        const questAccount = await program.account.quest.fetch(quest);
+        expect(questAccount.totalRewardDistributed.toString()).to.equal(
+          "900000"
+        );
+      });
+
+      it("should reject a reserve larger than the quest amount", async () => {
+        const { quest } = await createTestQuest(
+          "reserve-too-large",
+          new anchor.BN(1000000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          5
+        );
+        try {
+          await setReserve(quest, new anchor.BN(1000001));
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("InvalidReserveAmount");
+        }
+      });
+    });
+
+    describe("rent estimate", () => {
+      it("should match the rent of a freshly created quest", async () => {
+        const { quest, escrow } = await createTestQuest(
+          "rent-estimate-quest",
+          new anchor.BN(100000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          1
+        );
+        const { winner, winnerTokenAccount } = await createTestWinner();
+        await sendTestReward(
+          quest,
+          escrow,
+          winner.publicKey,
+          winnerTokenAccount,
+          new anchor.BN(100000)
+        );
+
+        // Allocated sizes of real accounts are the source of truth
+        const accountSizes = await Promise.all(
+          [
+            quest,
+            escrow,
+            questIdRegistryPDAFor("rent-estimate-quest"),
+            rewardClaimedPDAFor(quest, winner.publicKey),
+          ].map(
+            async (pubkey) =>
+              (await provider.connection.getAccountInfo(pubkey)).data.length
+          )
+        );
+        const rents = await Promise.all(
+          accountSizes.map((size) =>
+            provider.connection.getMinimumBalanceForRentExemption(size)
+          )
+        );
+
+        const withoutReward = await program.methods
+          .estimateQuestRent(false)
+          .accounts({})
+          .view();
+        const withReward = await program.methods
+          .estimateQuestRent(true)
+          .accounts({})
+          .view();
+
+        expect(withoutReward.toNumber()).to.be.greaterThan(0);
+        expect(withoutReward.toNumber()).to.equal(rents[0] + rents[1] + rents[2]);
+        expect(withReward.toNumber()).to.equal(
+          rents[0] + rents[1] + rents[2] + rents[3]
+        );
+      });
+    });
+
+    describe("batch rewards", () => {
+      // Each winner is passed as a [winner_token_account, reward_claimed] pair
+      const batchAccounts = (
+        quest: PublicKey,
+        winners: { winner: Keypair; winnerTokenAccount: PublicKey }[]
+      ) =>
+        winners.flatMap(({ winner, winnerTokenAccount }) => [
+          { pubkey: winnerTokenAccount, isWritable: true, isSigner: false },
+          {
+            pubkey: rewardClaimedPDAFor(quest, winner.publicKey),
+            isWritable: true,
+            isSigner: false,
+          },
+        ]);
+
+      const sendBatch = (
+        quest: PublicKey,
+        escrow: PublicKey,
+        winners: { winner: Keypair; winnerTokenAccount: PublicKey }[],
+        amounts: anchor.BN[]
+      ) =>
+        program.methods
+          .sendRewardBatch(
+            winners.map(({ winner }) => winner.publicKey),
+            amounts
+          )
+          .accounts({
+            owner: owner.publicKey,
+            globalState: globalStatePDA,
+            quest,
+            escrowAccount: escrow,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .remainingAccounts(batchAccounts(quest, winners))
+          .signers([owner])
+          .rpc();
+
+      it("should create a RewardClaimed record per winner in one batch", async () => {
+        const { quest, escrow } = await createTestQuest(
+          "batch-success-quest",
+          new anchor.BN(1000000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          5
+        );
+        const winners = [
+          await createTestWinner(),
+          await createTestWinner(),
+          await createTestWinner(),
+        ];
+        const amounts = [100000, 200000, 300000].map((a) => new anchor.BN(a));
+
+        await sendBatch(quest, escrow, winners, amounts);
+
+        for (const [i, { winner, winnerTokenAccount }] of winners.entries()) {
+          const record = await program.account.rewardClaimed.fetch(
+            rewardClaimedPDAFor(quest, winner.publicKey)
+          );
+          expect(record.winner.toString()).to.equal(
+            winner.publicKey.toString()
+          );
+          expect(record.rewardAmount.toString()).to.equal(
+            amounts[i].toString()
+          );
+          expect(record.claimed).to.be.true;
+          expect(
+            (
+              await getAccount(provider.connection, winnerTokenAccount)
+            ).amount.toString()
+          ).to.equal(amounts[i].toString());
+        }
+
+        const questAccount = await program.account.quest.fetch(quest);
+        expect(questAccount.totalWinners).to.equal(3);
+        expect(questAccount.totalRewardDistributed.toString()).to.equal(
+          "600000"
+        );
+      });
+
+      it("should roll back every record when one winner fails", async () => {
+        const { quest, escrow } = await createTestQuest(
+          "batch-failure-quest",
+          new anchor.BN(1000000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          5
+        );
+        const winners = [
+          await createTestWinner(),
+          await createTestWinner(),
+          await createTestWinner(),
+        ];
+        // Last winner is paid up front so the batch hits AlreadyRewarded
+        await sendTestReward(
+          quest,
+          escrow,
+          winners[2].winner.publicKey,
+          winners[2].winnerTokenAccount,
+          new anchor.BN(100000)
+        );
+
+        try {
+          await sendBatch(
+            quest,
+            escrow,
+            winners,
+            [100000, 100000, 100000].map((a) => new anchor.BN(a))
+          );
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("AlreadyRewarded");
+        }
+
+        for (const { winner } of winners.slice(0, 2)) {
+          expect(
+            await provider.connection.getAccountInfo(
+              rewardClaimedPDAFor(quest, winner.publicKey)
+            )
+          ).to.be.null;
+        }
+        const questAccount = await program.account.quest.fetch(quest);
+        expect(questAccount.totalWinners).to.equal(1);
+      });
+
+      it("should stop early instead of exhausting the compute budget", async () => {
+        const { quest, escrow } = await createTestQuest(
+          "batch-compute-quest",
+          new anchor.BN(1000000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          10
+        );
+        const winners = [];
+        for (let i = 0; i < 6; i++) {
+          winners.push(await createTestWinner());
+        }
+
+        // Too little compute for all six winners
+        await program.methods
+          .sendRewardBatch(
+            winners.map(({ winner }) => winner.publicKey),
+            winners.map(() => new anchor.BN(10000))
+          )
+          .accounts({
+            owner: owner.publicKey,
+            globalState: globalStatePDA,
+            quest,
+            escrowAccount: escrow,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .remainingAccounts(batchAccounts(quest, winners))
+          .preInstructions([
+            ComputeBudgetProgram.setComputeUnitLimit({ units: 150000 }),
+          ])
+          .signers([owner])
+          .rpc();
+
+        const questAccount = await program.account.quest.fetch(quest);
+        expect(questAccount.totalWinners).to.be.greaterThan(0);
+        expect(questAccount.totalWinners).to.be.lessThan(winners.length);
+        expect(questAccount.totalRewardDistributed.toNumber()).to.equal(
+          questAccount.totalWinners * 10000
+        );
+
+        // Only the processed prefix has records
+        for (const [i, { winner }] of winners.entries()) {
+          const info = await provider.connection.getAccountInfo(
+            rewardClaimedPDAFor(quest, winner.publicKey)
+          );
+          expect(info !== null).to.equal(i < questAccount.totalWinners);
+        }
+      });
+
+      it("should pay a batch at the size cap", async () => {
+        const { quest, escrow } = await createTestQuest(
+          "batch-cap-quest",
+          new anchor.BN(1000000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          10
+        );
+        const winners = [];
+        for (let i = 0; i < 7; i++) {
+          winners.push(await createTestWinner());
+        }
+
+        await program.methods
+          .sendRewardBatch(
+            winners.map(({ winner }) => winner.publicKey),
+            winners.map(() => new anchor.BN(10000))
+          )
+          .accounts({
+            owner: owner.publicKey,
+            globalState: globalStatePDA,
+            quest,
+            escrowAccount: escrow,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .remainingAccounts(batchAccounts(quest, winners))
+          .preInstructions([
+            ComputeBudgetProgram.setComputeUnitLimit({ units: 400000 }),
+          ])
+          .signers([owner])
+          .rpc();
+
+        const questAccount = await program.account.quest.fetch(quest);
+        expect(questAccount.totalWinners).to.equal(7);
+      });
+
+      it("should reject a batch over the size cap", async () => {
+        const { quest, escrow } = await createTestQuest(
+          "batch-oversize-quest",
+          new anchor.BN(1000000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          10
+        );
+        // Rejected before any per-winner accounts are read
+        const winners = Array.from({ length: 8 }, () => Keypair.generate().publicKey);
+        try {
+          await program.methods
+            .sendRewardBatch(
+              winners,
+              winners.map(() => new anchor.BN(10000))
+            )
+            .accounts({
+              owner: owner.publicKey,
+              globalState: globalStatePDA,
+              quest,
+              escrowAccount: escrow,
+              tokenProgram: TOKEN_PROGRAM_ID,
+              systemProgram: SystemProgram.programId,
+            })
+            .signers([owner])
+            .rpc();
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("BatchTooLarge");
+        }
+      });
+    });
+
+    describe("minimum quest duration", () => {
+      const setMinDuration = (seconds: number) =>
+        program.methods
+          .setMinQuestDuration(new anchor.BN(seconds))
+          .accounts({ owner: owner.publicKey, globalState: globalStatePDA })
+          .signers([owner])
+          .rpc();
+
+      before(async () => {
+        await setMinDuration(3600);
+      });
+
+      after(async () => {
+        // Other suites create quests with past deadlines
+        await setMinDuration(0);
+      });
+
+      it("should reject a quest shorter than the minimum duration", async () => {
+        try {
+          await createTestQuest(
+            "too-short-quest",
+            new anchor.BN(100000),
+            new anchor.BN(Math.floor(Date.now() / 1000) + 60),
+            1
+          );
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("QuestTooShort");
+        }
+      });
+
+      it("should accept a quest meeting the minimum duration", async () => {
+        const { quest } = await createTestQuest(
+          "long-enough-quest",
+          new anchor.BN(100000),
+          new anchor.BN(Math.floor(Date.now() / 1000) + 7200),
+          1
+        );
+        expect((await program.account.quest.fetch(quest)).isActive).to.be.true;
+      });
+    });
+
+    describe("config view", () => {
+      it("should return every owner-settable parameter", async () => {
+        await program.methods
+          .setMinQuestDuration(new anchor.BN(1800))
+          .accounts({ owner: owner.publicKey, globalState: globalStatePDA })
+          .signers([owner])
+          .rpc();
+
+        try {
+          const config = await program.methods
+            .getConfig()
+            .accounts({ globalState: globalStatePDA })
+            .view();
+          const state = await program.account.globalState.fetch(
+            globalStatePDA
+          );
+
+          expect(config.owner.toString()).to.equal(owner.publicKey.toString());
+          expect(config.paused).to.equal(state.paused);
+          expect(
+            config.supportedTokenMints.map((pk) => pk.toString())
+          ).to.deep.equal(state.supportedTokenMints.map((pk) => pk.toString()));
+          expect(config.minQuestDurationSeconds.toNumber()).to.equal(1800);
+          expect(config.enforceSupportedMintOnPayout).to.equal(
+            state.enforceSupportedMintOnPayout
+          );
+        } finally {
+          await program.methods
+            .setMinQuestDuration(new anchor.BN(0))
+            .accounts({ owner: owner.publicKey, globalState: globalStatePDA })
+            .signers([owner])
+            .rpc();
+        }
+      });
+    });
+
+    describe("quest reconciliation", () => {
+      it("should make surplus escrow funds distributable", async () => {
+        const { quest, escrow } = await createTestQuest(
+          "reconcile-quest",
+          new anchor.BN(500000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        );
+        const { winner, winnerTokenAccount } = await createTestWinner();
+        await sendTestReward(
+          quest,
+          escrow,
+          winner.publicKey,
+          winnerTokenAccount,
+          new anchor.BN(100000)
+        );
+
+        // Over-fund the escrow directly, bypassing the program
+        await transfer(
+          provider.connection,
+          owner,
+          await getAssociatedTokenAddress(
+            supportedTokenMint.publicKey,
+            owner.publicKey
+          ),
+          escrow,
+          owner,
+          200000
+        );
+
+        await program.methods
+          .reconcileQuest()
+          .accounts({ creator: owner.publicKey, quest, escrowAccount: escrow })
+          .signers([owner])
+          .rpc();
+
+        const questAccount = await program.account.quest.fetch(quest);
+        // 100000 paid + 600000 still in escrow
+        expect(questAccount.amount.toString()).to.equal("700000");
+        expect(questAccount.totalRewardDistributed.toString()).to.equal(
+          "100000"
+        );
+      });
+
+      it("should not allow non-creator to reconcile", async () => {
+        const { quest, escrow } = await createTestQuest(
+          "reconcile-unauthorized",
+          new anchor.BN(500000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        );
+        const { winner: stranger } = await createTestWinner();
+
+        try {
+          await program.methods
+            .reconcileQuest()
+            .accounts({
+              creator: stranger.publicKey,
+              quest,
+              escrowAccount: escrow,
+            })
+            .signers([stranger])
+            .rpc();
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal(
+            "UnauthorizedQuestUpdate"
+          );
+        }
+      });
+    });
+
+    describe("remaining reward event", () => {
+      it("should emit RemainingRewardClaimed with the refunded amount", async () => {
+        const { quest, escrow } = await createTestQuest(
+          "remaining-event-quest",
+          new anchor.BN(400000),
+          new anchor.BN(Date.now() / 1000 - 8 * 86400),
+          3
+        );
+        await program.methods
+          .updateQuestStatus(false)
+          .accounts({
+            owner: owner.publicKey,
+            globalState: globalStatePDA,
+            quest,
+          })
+          .signers([owner])
+          .rpc();
+
+        const signature = await program.methods
+          .claimRemainingReward()
+          .accounts({
+            claimer: owner.publicKey,
+            globalState: globalStatePDA,
+            quest,
+            escrowAccount: escrow,
+            creatorTokenAccount: await getAssociatedTokenAddress(
+              supportedTokenMint.publicKey,
+              owner.publicKey
+            ),
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .signers([owner])
+          .rpc({ commitment: "confirmed" });
+
+        const event = (await eventsFromTx(signature)).find(
+          (e) => e.name === "remainingRewardClaimed"
+        );
+        expect(event).to.exist;
+        expect(event.data.quest.toString()).to.equal(quest.toString());
+        expect(event.data.claimer.toString()).to.equal(
+          owner.publicKey.toString()
+        );
+        expect(event.data.amount.toString()).to.equal("400000");
+        expect(event.data.timestamp.toNumber()).to.be.greaterThan(0);
+        // The test owner is also the creator
+        expect(event.data.byCreator).to.be.true;
+        expect(event.data.recipient.toString()).to.equal(
+          (
+            await getAssociatedTokenAddress(
+              supportedTokenMint.publicKey,
+              owner.publicKey
+            )
+          ).toString()
+        );
+      });
+    });
+
+    describe("blocklist", () => {
+      const modifyBlocklist = (add: boolean, address: PublicKey) =>
+        (add
+          ? program.methods.addBlockedAddress(address)
+          : program.methods.removeBlockedAddress(address)
+        )
+          .accounts({
+            owner: owner.publicKey,
+            globalState: globalStatePDA,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([owner])
+          .rpc();
+
+      it("should reject rewards to a blocked winner until unblocked", async () => {
+        const { quest, escrow } = await createTestQuest(
+          "blocklist-quest",
+          new anchor.BN(500000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        );
+        const { winner, winnerTokenAccount } = await createTestWinner();
+
+        await modifyBlocklist(true, winner.publicKey);
+        const state = await program.account.globalState.fetch(globalStatePDA);
+        expect(state.blocklist.map((pk) => pk.toString())).to.include(
+          winner.publicKey.toString()
+        );
+
+        try {
+          await sendTestReward(
+            quest,
+            escrow,
+            winner.publicKey,
+            winnerTokenAccount,
+            new anchor.BN(100000)
+          );
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("AddressBlocked");
+        }
+
+        await modifyBlocklist(false, winner.publicKey);
+        await sendTestReward(
+          quest,
+          escrow,
+          winner.publicKey,
+          winnerTokenAccount,
+          new anchor.BN(100000)
+        );
+        const questAccount = await program.account.quest.fetch(quest);
+        expect(questAccount.totalWinners).to.equal(1);
+      });
+
+      it("should not allow non-owner to block addresses", async () => {
+        const { winner: nonOwner } = await createTestWinner();
+        try {
+          await program.methods
+            .addBlockedAddress(Keypair.generate().publicKey)
+            .accounts({
+              owner: nonOwner.publicKey,
+              globalState: globalStatePDA,
+              systemProgram: SystemProgram.programId,
+            })
+            .signers([nonOwner])
+            .rpc();
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal(
+            "UnauthorizedConfigUpdate"
+          );
+        }
+      });
+
+      it("should cap the blocklist", async () => {
+        const { blocklist } = await program.account.globalState.fetch(
+          globalStatePDA
+        );
+        const added: PublicKey[] = [];
+        for (let i = blocklist.length; i < 16; i++) {
+          const address = Keypair.generate().publicKey;
+          await modifyBlocklist(true, address);
+          added.push(address);
+        }
+
+        try {
+          try {
+            await modifyBlocklist(true, Keypair.generate().publicKey);
+            expect.fail("Expected the transaction to fail");
+          } catch (error) {
+            expect(error.error.errorCode.code).to.equal("BlocklistFull");
+          }
+        } finally {
+          for (const address of added) {
+            await modifyBlocklist(false, address);
+          }
+        }
+      });
+    });
+
+    describe("single reward cap", () => {
+      it("should cap a single send to a fraction of the remaining pool", async () => {
+        const { quest, escrow } = await createTestQuest(
+          "single-cap-quest",
+          new anchor.BN(1000000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          5
+        );
+        // 25% of whatever is left in escrow accounting
+        await program.methods
+          .setMaxSingleRewardBps(2500)
+          .accounts({ creator: owner.publicKey, quest })
+          .signers([owner])
+          .rpc();
+
+        const first = await createTestWinner();
+        try {
+          await sendTestReward(
+            quest,
+            escrow,
+            first.winner.publicKey,
+            first.winnerTokenAccount,
+            new anchor.BN(250001)
+          );
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("RewardExceedsSingleCap");
+        }
+
+        await sendTestReward(
+          quest,
+          escrow,
+          first.winner.publicKey,
+          first.winnerTokenAccount,
+          new anchor.BN(250000)
+        );
+
+        // Cap shrinks with the pool: 25% of 750000
+        const second = await createTestWinner();
+        try {
+          await sendTestReward(
+            quest,
+            escrow,
+            second.winner.publicKey,
+            second.winnerTokenAccount,
+            new anchor.BN(250000)
+          );
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("RewardExceedsSingleCap");
+        }
+        await sendTestReward(
+          quest,
+          escrow,
+          second.winner.publicKey,
+          second.winnerTokenAccount,
+          new anchor.BN(187500)
+        );
+      });
+    });
+
+    describe("time remaining", () => {
+      it("should return the on-chain countdown for a future deadline", async () => {
+        const now = Math.floor(Date.now() / 1000);
+        const { quest } = await createTestQuest(
+          "time-remaining-quest",
+          new anchor.BN(100000),
+          new anchor.BN(now + 3600),
+          1
+        );
+
+        const remaining = await program.methods
+          .getTimeRemaining()
+          .accounts({ quest })
+          .view();
+        // Allow for drift between the local and validator clocks
+        expect(remaining.toNumber()).to.be.within(3000, 3600);
+      });
+
+      it("should clamp to zero once the deadline has passed", async () => {
+        const { quest } = await createTestQuest(
+          "time-expired-quest",
+          new anchor.BN(100000),
+          new anchor.BN(Math.floor(Date.now() / 1000) - 3600),
+          1
+        );
+
+        const remaining = await program.methods
+          .getTimeRemaining()
+          .accounts({ quest })
+          .view();
+        expect(remaining.toNumber()).to.equal(0);
+      });
+    });
+
+    describe("close reward records of cancelled quests", () => {
+      const closeForQuest = (quest: PublicKey, winners: PublicKey[]) =>
+        program.methods
+          .closeRewardClaimedForCancelled()
+          .accounts({
+            owner: owner.publicKey,
+            globalState: globalStatePDA,
+            quest,
+          })
+          .remainingAccounts(
+            winners.flatMap((winner) =>
+              [rewardClaimedPDAFor(quest, winner), winner].map((pubkey) => ({
+                pubkey,
+                isWritable: true,
+                isSigner: false,
+              }))
+            )
+          )
+          .signers([owner])
+          .rpc();
+
+      const cancel = async (quest: PublicKey, escrow: PublicKey) =>
+        program.methods
+          .cancelQuest()
+          .accounts({
+            creator: owner.publicKey,
+            globalState: globalStatePDA,
+            quest,
+            escrowAccount: escrow,
+            creatorTokenAccount: await getAssociatedTokenAddress(
+              supportedTokenMint.publicKey,
+              owner.publicKey
+            ),
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .signers([owner])
+          .rpc();
+
+      it("should close records of a cancelled quest", async () => {
+        const { quest, escrow } = await createTestQuest(
+          "cancelled-close-quest",
+          new anchor.BN(500000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        );
+        const winners: PublicKey[] = [];
+        for (let i = 0; i < 2; i++) {
+          const { winner, winnerTokenAccount } = await createTestWinner();
+          await sendTestReward(
+            quest,
+            escrow,
+            winner.publicKey,
+            winnerTokenAccount,
+            new anchor.BN(100000)
+          );
+          winners.push(winner.publicKey);
+        }
+
+        await cancel(quest, escrow);
+        expect((await program.account.quest.fetch(quest)).cancelled).to.be.true;
+
+        await closeForQuest(quest, winners);
+
+        for (const winner of winners) {
+          expect(
+            await provider.connection.getAccountInfo(rewardClaimedPDAFor(quest, winner))
+          ).to.be.null;
+        }
+      });
+
+      it("should return the rent of a record the owner did not fund", async () => {
+        const { quest, escrow } = await createTestQuest(
+          "cancelled-close-payer-quest",
+          new anchor.BN(500000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        );
+        const { winner, winnerTokenAccount } = await createTestWinner();
+        const record = rewardClaimedPDAFor(quest, winner.publicKey);
+        await program.methods
+          .sendReward(new anchor.BN(100000))
+          .accounts({
+            owner: owner.publicKey,
+            payer: winner.publicKey,
+            globalState: globalStatePDA,
+            quest,
+            escrowAccount: escrow,
+            winner: winner.publicKey,
+            winnerTokenAccount,
+            rewardClaimed: record,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([owner, winner])
+          .rpc();
+        await cancel(quest, escrow);
+
+        const rent = await provider.connection.getBalance(record);
+        const winnerLamportsBefore = await provider.connection.getBalance(
+          winner.publicKey
+        );
+        await closeForQuest(quest, [winner.publicKey]);
+
+        expect(await provider.connection.getBalance(winner.publicKey)).to.equal(
+          winnerLamportsBefore + rent
+        );
+      });
+
+      it("should reject a rent recipient other than the winner", async () => {
+        const { quest, escrow } = await createTestQuest(
+          "cancelled-close-recipient-quest",
+          new anchor.BN(500000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        );
+        const { winner, winnerTokenAccount } = await createTestWinner();
+        await sendTestReward(
+          quest,
+          escrow,
+          winner.publicKey,
+          winnerTokenAccount,
+          new anchor.BN(100000)
+        );
+        await cancel(quest, escrow);
+
+        try {
+          await program.methods
+            .closeRewardClaimedForCancelled()
+            .accounts({
+              owner: owner.publicKey,
+              globalState: globalStatePDA,
+              quest,
+            })
+            .remainingAccounts(
+              [rewardClaimedPDAFor(quest, winner.publicKey), owner.publicKey].map(
+                (pubkey) => ({ pubkey, isWritable: true, isSigner: false })
+              )
+            )
+            .signers([owner])
+            .rpc();
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("InvalidRentRecipient");
+        }
+      });
+
+      it("should reject closing records of an active quest", async () => {
+        const { quest, escrow } = await createTestQuest(
+          "active-close-quest",
+          new anchor.BN(500000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        );
+        const { winner, winnerTokenAccount } = await createTestWinner();
+        await sendTestReward(
+          quest,
+          escrow,
+          winner.publicKey,
+          winnerTokenAccount,
+          new anchor.BN(100000)
+        );
+
+        try {
+          await closeForQuest(quest, [winner.publicKey]);
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("QuestNotCancelled");
+        }
+      });
+    });
+
+    describe("reward record payer", () => {
+      const sendWithPayer = (
+        quest: PublicKey,
+        escrow: PublicKey,
+        winner: PublicKey,
+        winnerTokenAccount: PublicKey,
+        payer: Keypair
+      ) =>
+        program.methods
+          .sendReward(new anchor.BN(100000))
+          .accounts({
+            owner: owner.publicKey,
+            payer: payer.publicKey,
+            globalState: globalStatePDA,
+            quest,
+            escrowAccount: escrow,
+            winner,
+            winnerTokenAccount,
+            rewardClaimed: rewardClaimedPDAFor(quest, winner),
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers(payer === owner ? [owner] : [owner, payer])
+          .rpc();
+
+      let quest: PublicKey;
+      let escrow: PublicKey;
+
+      before(async () => {
+        ({ quest, escrow } = await createTestQuest(
+          "payer-quest",
+          new anchor.BN(500000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        ));
+      });
+
+      it("should let the owner fund the record", async () => {
+        const { winner, winnerTokenAccount } = await createTestWinner();
+        const winnerLamportsBefore = await provider.connection.getBalance(
+          winner.publicKey
+        );
+
+        await sendWithPayer(
+          quest,
+          escrow,
+          winner.publicKey,
+          winnerTokenAccount,
+          owner
+        );
+
+        expect(await provider.connection.getBalance(winner.publicKey)).to.equal(
+          winnerLamportsBefore
+        );
+      });
+
+      it("should let the winner fund their own record", async () => {
+        const { winner, winnerTokenAccount } = await createTestWinner();
+        const winnerLamportsBefore = await provider.connection.getBalance(
+          winner.publicKey
+        );
+
+        await sendWithPayer(
+          quest,
+          escrow,
+          winner.publicKey,
+          winnerTokenAccount,
+          winner
+        );
+
+        const rent = await provider.connection.getBalance(
+          rewardClaimedPDAFor(quest, winner.publicKey)
+        );
+        expect(rent).to.be.greaterThan(0);
+        expect(
+          await provider.connection.getBalance(winner.publicKey)
+        ).to.be.at.most(winnerLamportsBefore - rent);
+      });
+    });
+
+    describe("escrow self-transfer guard", () => {
+      it("should reject the escrow as the winner token account", async () => {
+        const { quest, escrow } = await createTestQuest(
+          "escrow-self-transfer-quest",
+          new anchor.BN(500000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        );
+
+        // The escrow is owned by global_state, so that is the only "winner"
+        // that could pass the ownership constraint
+        try {
+          await sendTestReward(
+            quest,
+            escrow,
+            globalStatePDA,
+            escrow,
+            new anchor.BN(100000)
+          );
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("InvalidTokenAccount");
+        }
+      });
+    });
+
+    describe("quest reactivation", () => {
+      const reactivate = async (
+        quest: PublicKey,
+        escrow: PublicKey,
+        amount: anchor.BN
+      ) =>
+        program.methods
+          .reactivateQuest(amount)
+          .accounts({
+            creator: owner.publicKey,
+            globalState: globalStatePDA,
+            quest,
+            escrowAccount: escrow,
+            creatorTokenAccount: await getAssociatedTokenAddress(
+              supportedTokenMint.publicKey,
+              owner.publicKey
+            ),
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .signers([owner])
+          .rpc();
+
+      it("should re-fund and reactivate a cancelled quest", async () => {
+        const { quest, escrow } = await createTestQuest(
+          "reactivate-quest",
+          new anchor.BN(500000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        );
+        const { winner, winnerTokenAccount } = await createTestWinner();
+        await sendTestReward(
+          quest,
+          escrow,
+          winner.publicKey,
+          winnerTokenAccount,
+          new anchor.BN(100000)
+        );
+
+        await program.methods
+          .cancelQuest()
+          .accounts({
+            creator: owner.publicKey,
+            globalState: globalStatePDA,
+            quest,
+            escrowAccount: escrow,
+            creatorTokenAccount: await getAssociatedTokenAddress(
+              supportedTokenMint.publicKey,
+              owner.publicKey
+            ),
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .signers([owner])
+          .rpc();
+
+        await reactivate(quest, escrow, new anchor.BN(300000));
+
+        const questAccount = await program.account.quest.fetch(quest);
+        expect(questAccount.isActive).to.be.true;
+        expect(questAccount.cancelled).to.be.false;
+        expect(questAccount.totalWinners).to.equal(1);
+        expect(questAccount.totalRewardDistributed.toNumber()).to.equal(100000);
+        expect(questAccount.amount.toNumber()).to.equal(400000);
+        const escrowAccount = await getAccount(provider.connection, escrow);
+        expect(Number(escrowAccount.amount)).to.equal(300000);
+      });
+
+      it("should reject reactivating a quest that was not cancelled", async () => {
+        const { quest, escrow } = await createTestQuest(
+          "completed-reactivate-quest",
+          new anchor.BN(500000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        );
+        await program.methods
+          .updateQuestStatus(false)
+          .accounts({
+            owner: owner.publicKey,
+            globalState: globalStatePDA,
+            quest,
+          })
+          .signers([owner])
+          .rpc();
+
+        try {
+          await reactivate(quest, escrow, new anchor.BN(300000));
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("QuestNotCancelled");
+        }
+      });
+
+      it("should not reactivate a quest whose reward records were closed", async () => {
+        const { quest, escrow } = await createTestQuest(
+          "reactivate-closed-records-quest",
+          new anchor.BN(500000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        );
+        const { winner, winnerTokenAccount } = await createTestWinner();
+        await sendTestReward(
+          quest,
+          escrow,
+          winner.publicKey,
+          winnerTokenAccount,
+          new anchor.BN(100000)
+        );
+        await program.methods
+          .cancelQuest()
+          .accounts({
+            creator: owner.publicKey,
+            globalState: globalStatePDA,
+            quest,
+            escrowAccount: escrow,
+            creatorTokenAccount: await getAssociatedTokenAddress(
+              supportedTokenMint.publicKey,
+              owner.publicKey
+            ),
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .signers([owner])
+          .rpc();
+        await program.methods
+          .closeRewardClaimedForCancelled()
+          .accounts({
+            owner: owner.publicKey,
+            globalState: globalStatePDA,
+            quest,
+          })
+          .remainingAccounts(
+            [rewardClaimedPDAFor(quest, winner.publicKey), winner.publicKey].map(
+              (pubkey) => ({ pubkey, isWritable: true, isSigner: false })
+            )
+          )
+          .signers([owner])
+          .rpc();
+
+        // Reopening would let the winner whose record is gone be paid again
+        try {
+          await reactivate(quest, escrow, new anchor.BN(300000));
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("RewardRecordsClosed");
+        }
+      });
+
+      it("should keep the owner's pause across cancel and reactivate", async () => {
+        const { quest, escrow } = await createTestQuest(
+          "reactivate-paused-quest",
+          new anchor.BN(500000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        );
+        await program.methods
+          .cancelQuest()
+          .accounts({
+            creator: owner.publicKey,
+            globalState: globalStatePDA,
+            quest,
+            escrowAccount: escrow,
+            creatorTokenAccount: await getAssociatedTokenAddress(
+              supportedTokenMint.publicKey,
+              owner.publicKey
+            ),
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .signers([owner])
+          .rpc();
+        await program.methods
+          .pauseQuests()
+          .accounts({ owner: owner.publicKey, globalState: globalStatePDA })
+          .remainingAccounts([{ pubkey: quest, isWritable: true, isSigner: false }])
+          .signers([owner])
+          .rpc();
+
+        await reactivate(quest, escrow, new anchor.BN(300000));
+
+        const questAccount = await program.account.quest.fetch(quest);
+        expect(questAccount.isActive).to.be.true;
+        expect(questAccount.paused).to.be.true;
+      });
+    });
+
+    describe("supported mint check at payout", () => {
+      const setEnforce = (enabled: boolean) =>
+        program.methods
+          .setEnforceSupportedMintOnPayout(enabled)
+          .accounts({ owner: owner.publicKey, globalState: globalStatePDA })
+          .signers([owner])
+          .rpc();
+
+      const modifyToken = (add: boolean) =>
+        (add
+          ? program.methods.addSupportedToken()
+          : program.methods.removeSupportedToken()
+        )
+          .accounts({
+            owner: owner.publicKey,
+            globalState: globalStatePDA,
+            tokenMint: supportedTokenMint.publicKey,
+          })
+          .signers([owner])
+          .rpc();
+
+      let quest: PublicKey;
+      let escrow: PublicKey;
+
+      before(async () => {
+        ({ quest, escrow } = await createTestQuest(
+          "mint-check-quest",
+          new anchor.BN(1000000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          10
+        ));
+      });
+
+      const payNewWinner = async () => {
+        const { winner, winnerTokenAccount } = await createTestWinner();
+        await sendTestReward(
+          quest,
+          escrow,
+          winner.publicKey,
+          winnerTokenAccount,
+          new anchor.BN(10000)
+        );
+      };
+
+      it("should pay out on a still-supported mint under both settings", async () => {
+        await payNewWinner();
+
+        await setEnforce(true);
+        try {
+          await payNewWinner();
+        } finally {
+          await setEnforce(false);
+        }
+      });
+
+      it("should pay out on a de-listed mint only when the check is off", async () => {
+        await modifyToken(false);
+        try {
+          await payNewWinner();
+
+          await setEnforce(true);
+          try {
+            await payNewWinner();
+            expect.fail("Expected the transaction to fail");
+          } catch (error) {
+            expect(error.error.errorCode.code).to.equal("UnsupportedTokenMint");
+          } finally {
+            await setEnforce(false);
+          }
+        } finally {
+          await modifyToken(true);
+        }
+      });
+    });
+
+    describe("authorization check", () => {
+      const checkAuthorization = (signer: Keypair, action: number) =>
+        program.methods
+          .checkAuthorization(action)
+          .accounts({ signer: signer.publicKey, globalState: globalStatePDA })
+          .signers([signer])
+          .view();
+
+      // Every code from pause (1) through audit log initialization (26)
+      const actions = Array.from({ length: 26 }, (_, i) => i + 1);
+
+      it("should authorize the owner for every action", async () => {
+        for (const action of actions) {
+          expect(await checkAuthorization(owner, action)).to.be.true;
+        }
+      });
+
+      it("should not authorize other signers", async () => {
+        const nonOwner = Keypair.generate();
+        for (const action of actions) {
+          expect(await checkAuthorization(nonOwner, action)).to.be.false;
+        }
+      });
+
+      it("should reject an unknown action code", async () => {
+        try {
+          await checkAuthorization(owner, 0);
+          expect.fail("Expected the view to fail");
+        } catch (error) {
+          expect(error.toString()).to.include("UnknownAction");
+        }
+      });
+    });
+
+    describe("default deadline", () => {
+      const setDefaultDuration = (seconds: number) =>
+        program.methods
+          .setDefaultQuestDuration(new anchor.BN(seconds))
+          .accounts({ owner: owner.publicKey, globalState: globalStatePDA })
+          .signers([owner])
+          .rpc();
+
+      before(async () => {
+        await setDefaultDuration(86400);
+      });
+
+      after(async () => {
+        await setDefaultDuration(0);
+      });
+
+      it("should keep an explicit deadline", async () => {
+        const deadline = new anchor.BN(Math.floor(Date.now() / 1000) + 3600);
+        const { quest } = await createTestQuest(
+          "explicit-deadline-quest",
+          new anchor.BN(500000),
+          deadline,
+          3
+        );
+
+        const questAccount = await program.account.quest.fetch(quest);
+        expect(questAccount.deadline.toNumber()).to.equal(deadline.toNumber());
+      });
+
+      it("should resolve the sentinel to now plus the default duration", async () => {
+        const before = Math.floor(Date.now() / 1000);
+        const { quest } = await createTestQuest(
+          "default-deadline-quest",
+          new anchor.BN(500000),
+          new anchor.BN(0),
+          3
+        );
+
+        const questAccount = await program.account.quest.fetch(quest);
+        const config = await program.methods
+          .getConfig()
+          .accounts({ globalState: globalStatePDA })
+          .view();
+        expect(config.defaultQuestDurationSeconds.toNumber()).to.equal(86400);
+        // Allow for drift between the local and validator clocks
+        expect(questAccount.deadline.toNumber()).to.be.closeTo(
+          before + 86400,
+          60
+        );
+      });
+
+      it("should reject the sentinel when no default is configured", async () => {
+        await setDefaultDuration(0);
+        try {
+          await createTestQuest(
+            "no-default-deadline-quest",
+            new anchor.BN(500000),
+            new anchor.BN(0),
+            3
+          );
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("InvalidDeadline");
+        } finally {
+          await setDefaultDuration(86400);
+        }
+      });
+    });
+
+    describe("finalized quests", () => {
+      it("should reject cancelling once the remaining reward was claimed", async () => {
+        const { quest, escrow } = await createTestQuest(
+          "finalized-cancel-quest",
+          new anchor.BN(400000),
+          new anchor.BN(Date.now() / 1000 - 8 * 86400),
+          3
+        );
+        const setStatus = (isActive: boolean) =>
+          program.methods
+            .updateQuestStatus(isActive)
+            .accounts({
+              owner: owner.publicKey,
+              globalState: globalStatePDA,
+              quest,
+            })
+            .signers([owner])
+            .rpc();
+        const creatorTokenAccount = await getAssociatedTokenAddress(
+          supportedTokenMint.publicKey,
+          owner.publicKey
+        );
+
+        await setStatus(false);
+        await program.methods
+          .claimRemainingReward()
+          .accounts({
+            claimer: owner.publicKey,
+            globalState: globalStatePDA,
+            quest,
+            escrowAccount: escrow,
+            creatorTokenAccount,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .signers([owner])
+          .rpc();
+        expect((await program.account.quest.fetch(quest)).finalized).to.be
+          .true;
+
+        // Even a quest flipped back to active stays terminal
+        await setStatus(true);
+        try {
+          await program.methods
+            .cancelQuest()
+            .accounts({
+              creator: owner.publicKey,
+              globalState: globalStatePDA,
+              quest,
+              escrowAccount: escrow,
+              creatorTokenAccount,
+              tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .signers([owner])
+            .rpc();
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("QuestFinalized");
+        }
+      });
+    });
+
+    describe("escrow address", () => {
+      it("should match the client-side derivation", async () => {
+        const { quest, escrow } = await createTestQuest(
+          "escrow-address-quest",
+          new anchor.BN(500000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        );
+
+        const derived = await program.methods
+          .getEscrowAddress()
+          .accounts({ quest })
+          .view();
+        expect(derived.toString()).to.equal(escrowPDAFor(quest).toString());
+        expect(derived.toString()).to.equal(escrow.toString());
+      });
+    });
+
+    describe("base unit conversion", () => {
+      const toBaseUnits = (wholeAmount: anchor.BN) =>
+        program.methods
+          .toBaseUnits(wholeAmount)
+          .accounts({ tokenMint: supportedTokenMint.publicKey })
+          .view();
+
+      it("should scale whole tokens by the mint decimals", async () => {
+        // The supported test mint has 9 decimals
+        const baseUnits = await toBaseUnits(new anchor.BN(25));
+        expect(baseUnits.toString()).to.equal("25000000000");
+      });
+
+      it("should reject amounts that overflow u64", async () => {
+        try {
+          // u64::MAX / 10^9 is about 1.8e10
+          await toBaseUnits(new anchor.BN("20000000000"));
+          expect.fail("Expected the view to fail");
+        } catch (error) {
+          expect(error.toString()).to.include("ArithmeticOverflow");
+        }
+      });
+    });
+
+    describe("owner quest freeze", () => {
+      const setFrozen = (quest: PublicKey, frozen: boolean, signer = owner) =>
+        (frozen
+          ? program.methods.ownerFreezeQuest()
+          : program.methods.ownerUnfreezeQuest()
+        )
+          .accounts({
+            owner: signer.publicKey,
+            globalState: globalStatePDA,
+            quest,
+          })
+          .signers([signer])
+          .rpc();
+
+      it("should block payouts while frozen and restore them after", async () => {
+        const { quest, escrow } = await createTestQuest(
+          "owner-freeze-quest",
+          new anchor.BN(500000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        );
+        const { winner, winnerTokenAccount } = await createTestWinner();
+
+        await setFrozen(quest, true);
+        try {
+          await sendTestReward(
+            quest,
+            escrow,
+            winner.publicKey,
+            winnerTokenAccount,
+            new anchor.BN(100000)
+          );
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("QuestFrozen");
+        }
+
+        await setFrozen(quest, false);
+        await sendTestReward(
+          quest,
+          escrow,
+          winner.publicKey,
+          winnerTokenAccount,
+          new anchor.BN(100000)
+        );
+        const questAccount = await program.account.quest.fetch(quest);
+        expect(questAccount.ownerFrozen).to.be.false;
+        expect(questAccount.totalWinners).to.equal(1);
+      });
+
+      it("should not allow non-owner to freeze a quest", async () => {
+        const { winner: nonOwner } = await createTestWinner();
+        const { quest } = await createTestQuest(
+          "owner-freeze-quest-2",
+          new anchor.BN(500000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        );
+
+        try {
+          await setFrozen(quest, true, nonOwner);
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("UnauthorizedPauseAction");
+        }
+      });
+
+      it("should not let the creator cancel a frozen quest", async () => {
+        const { quest, escrow } = await createTestQuest(
+          "owner-freeze-cancel-quest",
+          new anchor.BN(500000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        );
+
+        await setFrozen(quest, true);
+        try {
+          await program.methods
+            .cancelQuest()
+            .accounts({
+              creator: owner.publicKey,
+              globalState: globalStatePDA,
+              quest,
+              escrowAccount: escrow,
+              creatorTokenAccount: await getAssociatedTokenAddress(
+                supportedTokenMint.publicKey,
+                owner.publicKey
+              ),
+              tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .signers([owner])
+            .rpc();
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("QuestFrozen");
+        }
+        expect(
+          (await getAccount(provider.connection, escrow)).amount.toString()
+        ).to.equal("500000");
+      });
+    });
+
+    describe("quest context", () => {
+      it("should return global state, quest and reward record together", async () => {
+        const { quest, escrow } = await createTestQuest(
+          "quest-context-quest",
+          new anchor.BN(500000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        );
+        const { winner, winnerTokenAccount } = await createTestWinner();
+        await sendTestReward(
+          quest,
+          escrow,
+          winner.publicKey,
+          winnerTokenAccount,
+          new anchor.BN(100000)
+        );
+        const rewardClaimed = rewardClaimedPDAFor(quest, winner.publicKey);
+
+        const context = await program.methods
+          .getQuestContext()
+          .accounts({ globalState: globalStatePDA, quest })
+          .remainingAccounts([
+            { pubkey: rewardClaimed, isWritable: false, isSigner: false },
+          ])
+          .view();
+
+        const state = await program.account.globalState.fetch(globalStatePDA);
+        const questAccount = await program.account.quest.fetch(quest);
+        const record = await program.account.rewardClaimed.fetch(rewardClaimed);
+        expect(context.global.owner.toString()).to.equal(state.owner.toString());
+        expect(context.global.questCount).to.equal(state.questCount);
+        expect(context.global.mintSupported).to.be.true;
+        expect(context.quest.id).to.equal(questAccount.id);
+        expect(context.quest.totalWinners).to.equal(questAccount.totalWinners);
+        expect(context.rewardClaimed.winner.toString()).to.equal(
+          record.winner.toString()
+        );
+        expect(context.rewardClaimed.rewardAmount.toNumber()).to.equal(100000);
+
+        const withoutRecord = await program.methods
+          .getQuestContext()
+          .accounts({ globalState: globalStatePDA, quest })
+          .view();
+        expect(withoutRecord.rewardClaimed).to.be.null;
+      });
+    });
+
+    describe("stale escrow guard", () => {
+      const createWithQuestKeypair = async (questKeypair: Keypair) =>
+        program.methods
+          .createQuest(
+            "stale-escrow-quest",
+            new anchor.BN(500000),
+            new anchor.BN(Date.now() / 1000 + 86400),
+            3
+          )
+          .accounts({
+            creator: owner.publicKey,
+            globalState: globalStatePDA,
+            tokenMint: supportedTokenMint.publicKey,
+            escrowAccount: escrowPDAFor(questKeypair.publicKey),
+            creatorTokenAccount: await getAssociatedTokenAddress(
+              supportedTokenMint.publicKey,
+              owner.publicKey
+            ),
+            quest: questKeypair.publicKey,
+            questIdRegistry: questIdRegistryPDAFor("stale-escrow-quest"),
+            systemProgram: SystemProgram.programId,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+          })
+          .signers([owner, questKeypair])
+          .rpc();
+
+      it("should reject a quest whose escrow address is already taken", async () => {
+        // The validator preloads a token account at this quest's escrow PDA
+        const questKeypair = Keypair.fromSecretKey(
+          Uint8Array.from(
+            JSON.parse(
+              readFileSync("tests/fixtures/stale-escrow-quest.json", "utf8")
+            )
+          )
+        );
+
+        try {
+          await createWithQuestKeypair(questKeypair);
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("EscrowAlreadyExists");
+        }
+      });
+
+      it("should tolerate lamports already sent to the escrow address", async () => {
+        const questKeypair = Keypair.generate();
+        const escrow = escrowPDAFor(questKeypair.publicKey);
+        await provider.sendAndConfirm(
+          new Transaction().add(
+            SystemProgram.transfer({
+              fromPubkey: owner.publicKey,
+              toPubkey: escrow,
+              lamports: 1_000_000,
+            })
+          ),
+          [owner]
+        );
+
+        await createWithQuestKeypair(questKeypair);
+
+        const escrowAccount = await getAccount(provider.connection, escrow);
+        expect(Number(escrowAccount.amount)).to.equal(500000);
+        expect(escrowAccount.owner.toString()).to.equal(
+          globalStatePDA.toString()
+        );
+      });
+    });
+
+    describe("batch token removal", () => {
+      const addNewMint = async (): Promise<PublicKey> => {
+        const mint = Keypair.generate();
+        await createMint(
+          provider.connection,
+          owner,
+          owner.publicKey,
+          null,
+          9,
+          mint
+        );
+        await program.methods
+          .addSupportedToken()
+          .accounts({
+            owner: owner.publicKey,
+            globalState: globalStatePDA,
+            tokenMint: mint.publicKey,
+          })
+          .signers([owner])
+          .rpc();
+        return mint.publicKey;
+      };
+
+      const removeMints = (mints: PublicKey[]) =>
+        program.methods
+          .removeSupportedTokens(mints)
+          .accounts({ owner: owner.publicKey, globalState: globalStatePDA })
+          .signers([owner])
+          .rpc();
+
+      const supportedMints = async () =>
+        (
+          await program.account.globalState.fetch(globalStatePDA)
+        ).supportedTokenMints.map((pk) => pk.toString());
+
+      it("should remove two tokens in one call keeping the order", async () => {
+        const before = await supportedMints();
+        const first = await addNewMint();
+        const second = await addNewMint();
+
+        await removeMints([first, second]);
+
+        expect(await supportedMints()).to.deep.equal(before);
+      });
+
+      it("should revert the whole call on a not-found entry", async () => {
+        const mint = await addNewMint();
+        const before = await supportedMints();
+
+        try {
+          await removeMints([mint, Keypair.generate().publicKey]);
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("TokenNotFound");
+        }
+        expect(await supportedMints()).to.deep.equal(before);
+
+        await removeMints([mint]);
+      });
+    });
+
+    describe("weighted rewards", () => {
+      const sendWeighted = (
+        quest: PublicKey,
+        escrow: PublicKey,
+        winner: PublicKey,
+        winnerTokenAccount: PublicKey,
+        weight: number,
+        totalWeight: number,
+        pool: number
+      ) =>
+        program.methods
+          .sendRewardWeighted(
+            new anchor.BN(weight),
+            new anchor.BN(totalWeight),
+            new anchor.BN(pool)
+          )
+          .accounts({
+            owner: owner.publicKey,
+            payer: owner.publicKey,
+            globalState: globalStatePDA,
+            quest,
+            escrowAccount: escrow,
+            winner,
+            winnerTokenAccount,
+            rewardClaimed: rewardClaimedPDAFor(quest, winner),
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([owner])
+          .rpc();
+
+      it("should split the pool proportionally between two winners", async () => {
+        const { quest, escrow } = await createTestQuest(
+          "weighted-quest",
+          new anchor.BN(500000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        );
+        const first = await createTestWinner();
+        const second = await createTestWinner();
+
+        await sendWeighted(
+          quest,
+          escrow,
+          first.winner.publicKey,
+          first.winnerTokenAccount,
+          1,
+          3,
+          300000
+        );
+        await sendWeighted(
+          quest,
+          escrow,
+          second.winner.publicKey,
+          second.winnerTokenAccount,
+          2,
+          3,
+          300000
+        );
+
+        const firstAccount = await getAccount(
+          provider.connection,
+          first.winnerTokenAccount
+        );
+        const secondAccount = await getAccount(
+          provider.connection,
+          second.winnerTokenAccount
+        );
+        expect(Number(firstAccount.amount)).to.equal(100000);
+        expect(Number(secondAccount.amount)).to.equal(200000);
+
+        const questAccount = await program.account.quest.fetch(quest);
+        expect(questAccount.weightedDistributed.toNumber()).to.equal(300000);
+        expect(questAccount.totalRewardDistributed.toNumber()).to.equal(300000);
+      });
+
+      it("should reject a zero total weight", async () => {
+        const { quest, escrow } = await createTestQuest(
+          "weighted-zero-quest",
+          new anchor.BN(500000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        );
+        const { winner, winnerTokenAccount } = await createTestWinner();
+
+        try {
+          await sendWeighted(
+            quest,
+            escrow,
+            winner.publicKey,
+            winnerTokenAccount,
+            0,
+            0,
+            300000
+          );
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("InvalidWeight");
+        }
+      });
+    });
+
+    describe("owner max winners override", () => {
+      const overrideMaxWinners = (quest: PublicKey, newMax: number) =>
+        program.methods
+          .ownerSetMaxWinners(newMax)
+          .accounts({
+            owner: owner.publicKey,
+            globalState: globalStatePDA,
+            quest,
+          })
+          .signers([owner])
+          .rpc({ commitment: "confirmed" });
+
+      let quest: PublicKey;
+
+      before(async () => {
+        let escrow: PublicKey;
+        ({ quest, escrow } = await createTestQuest(
+          "owner-max-winners-quest",
+          new anchor.BN(500000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          2
+        ));
+        for (let i = 0; i < 2; i++) {
+          const { winner, winnerTokenAccount } = await createTestWinner();
+          await sendTestReward(
+            quest,
+            escrow,
+            winner.publicKey,
+            winnerTokenAccount,
+            new anchor.BN(10000)
+          );
+        }
+      });
+
+      it("should let the owner raise max winners and emit an event", async () => {
+        const signature = await overrideMaxWinners(quest, 5);
+
+        expect((await program.account.quest.fetch(quest)).maxWinners).to.equal(
+          5
+        );
+        const event = (await eventsFromTx(signature)).find(
+          (e) => e.name === "maxWinnersOverridden"
+        );
+        expect(event).to.exist;
+        expect(event.data.oldMaxWinners).to.equal(2);
+        expect(event.data.newMaxWinners).to.equal(5);
+      });
+
+      it("should reject a max below the winners already paid", async () => {
+        try {
+          await overrideMaxWinners(quest, 1);
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("MaxWinnersBelowTotal");
+        }
+      });
+    });
+
+    describe("distribution invariant", () => {
+      it("should hold when a quest is paid out to the exact boundary", async () => {
+        const { quest, escrow } = await createTestQuest(
+          "invariant-boundary-quest",
+          new anchor.BN(100000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        );
+        for (const amount of [99999, 1]) {
+          const { winner, winnerTokenAccount } = await createTestWinner();
+          await sendTestReward(
+            quest,
+            escrow,
+            winner.publicKey,
+            winnerTokenAccount,
+            new anchor.BN(amount)
+          );
+        }
+
+        const questAccount = await program.account.quest.fetch(quest);
+        expect(questAccount.totalRewardDistributed.toNumber()).to.equal(
+          questAccount.amount.toNumber()
+        );
+
+        // One more unit would cross the boundary and is refused up front
+        const { winner, winnerTokenAccount } = await createTestWinner();
+        try {
+          await sendTestReward(
+            quest,
+            escrow,
+            winner.publicKey,
+            winnerTokenAccount,
+            new anchor.BN(1)
+          );
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal(
+            "InsufficientRewardBalance"
+          );
+        }
+      });
+    });
+
+    describe("quest split", () => {
+      it("should move funds and winner slots into a new quest", async () => {
+        const { quest, escrow } = await createTestQuest(
+          "split-source-quest",
+          new anchor.BN(1000000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          10
+        );
+        const { winner, winnerTokenAccount } = await createTestWinner();
+        await sendTestReward(
+          quest,
+          escrow,
+          winner.publicKey,
+          winnerTokenAccount,
+          new anchor.BN(100000)
+        );
+
+        const newQuest = Keypair.generate();
+        const newEscrow = escrowPDAFor(newQuest.publicKey);
+        await program.methods
+          .splitQuest("split-target-quest", new anchor.BN(300000))
+          .accounts({
+            creator: owner.publicKey,
+            globalState: globalStatePDA,
+            quest,
+            escrowAccount: escrow,
+            tokenMint: supportedTokenMint.publicKey,
+            newQuest: newQuest.publicKey,
+            questIdRegistry: questIdRegistryPDAFor("split-target-quest"),
+            newEscrowAccount: newEscrow,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([owner, newQuest])
+          .rpc();
+
+        const original = await program.account.quest.fetch(quest);
+        expect(original.amount.toNumber()).to.equal(700000);
+        expect(original.totalRewardDistributed.toNumber()).to.equal(100000);
+        expect(original.maxWinners).to.equal(7);
+
+        const split = await program.account.quest.fetch(newQuest.publicKey);
+        expect(split.id).to.equal("split-target-quest");
+        expect(split.amount.toNumber()).to.equal(300000);
+        expect(split.maxWinners).to.equal(3);
+        expect(split.escrowAccount.toString()).to.equal(newEscrow.toString());
+        expect(split.isActive).to.be.true;
+
+        const originalEscrow = await getAccount(provider.connection, escrow);
+        const splitEscrow = await getAccount(provider.connection, newEscrow);
+        expect(Number(originalEscrow.amount)).to.equal(600000);
+        expect(Number(splitEscrow.amount)).to.equal(300000);
+      });
+
+      it("should not split a frozen quest", async () => {
+        const { quest, escrow } = await createTestQuest(
+          "split-frozen-quest",
+          new anchor.BN(1000000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          10
+        );
+        await program.methods
+          .ownerFreezeQuest()
+          .accounts({
+            owner: owner.publicKey,
+            globalState: globalStatePDA,
+            quest,
+          })
+          .signers([owner])
+          .rpc();
+
+        const newQuest = Keypair.generate();
+        try {
+          await program.methods
+            .splitQuest("split-frozen-target", new anchor.BN(300000))
+            .accounts({
+              creator: owner.publicKey,
+              globalState: globalStatePDA,
+              quest,
+              escrowAccount: escrow,
+              tokenMint: supportedTokenMint.publicKey,
+              newQuest: newQuest.publicKey,
+              questIdRegistry: questIdRegistryPDAFor("split-frozen-target"),
+              newEscrowAccount: escrowPDAFor(newQuest.publicKey),
+              tokenProgram: TOKEN_PROGRAM_ID,
+              systemProgram: SystemProgram.programId,
+            })
+            .signers([owner, newQuest])
+            .rpc();
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("QuestFrozen");
+        }
+      });
+    });
+
+    describe("quest merge", () => {
+      const mergeQuests = (
+        quest: PublicKey,
+        escrow: PublicKey,
+        sourceQuest: PublicKey,
+        sourceEscrow: PublicKey
+      ) =>
+        program.methods
+          .mergeQuests()
+          .accounts({
+            creator: owner.publicKey,
+            globalState: globalStatePDA,
+            quest,
+            escrowAccount: escrow,
+            sourceQuest,
+            sourceEscrowAccount: sourceEscrow,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .signers([owner])
+          .rpc();
+
+      it("should combine two quests of the same mint", async () => {
+        const deadline = new anchor.BN(Date.now() / 1000 + 86400);
+        const first = await createTestQuest(
+          "merge-target-quest",
+          new anchor.BN(500000),
+          deadline,
+          3
+        );
+        const second = await createTestQuest(
+          "merge-source-quest",
+          new anchor.BN(300000),
+          deadline,
+          2
+        );
+        const { winner, winnerTokenAccount } = await createTestWinner();
+        await sendTestReward(
+          second.quest,
+          second.escrow,
+          winner.publicKey,
+          winnerTokenAccount,
+          new anchor.BN(100000)
+        );
+
+        await mergeQuests(first.quest, first.escrow, second.quest, second.escrow);
+
+        const merged = await program.account.quest.fetch(first.quest);
+        expect(merged.amount.toNumber()).to.equal(800000);
+        expect(merged.maxWinners).to.equal(5);
+        expect(merged.totalWinners).to.equal(1);
+        expect(merged.totalRewardDistributed.toNumber()).to.equal(100000);
+
+        const escrowAccount = await getAccount(provider.connection, first.escrow);
+        expect(Number(escrowAccount.amount)).to.equal(700000);
+        expect(await provider.connection.getAccountInfo(second.quest)).to.be
+          .null;
+        expect(await provider.connection.getAccountInfo(second.escrow)).to.be
+          .null;
+      });
+
+      it("should reject merging quests of different mints", async () => {
+        const otherMint = Keypair.generate();
+        await createMint(
+          provider.connection,
+          owner,
+          owner.publicKey,
+          null,
+          9,
+          otherMint
+        );
+        await program.methods
+          .addSupportedToken()
+          .accounts({
+            owner: owner.publicKey,
+            globalState: globalStatePDA,
+            tokenMint: otherMint.publicKey,
+          })
+          .signers([owner])
+          .rpc();
+        const ownerOtherAta = await getAssociatedTokenAddress(
+          otherMint.publicKey,
+          owner.publicKey
+        );
+        await provider.sendAndConfirm(
+          new Transaction().add(
+            createAssociatedTokenAccountInstruction(
+              owner.publicKey,
+              ownerOtherAta,
+              owner.publicKey,
+              otherMint.publicKey
+            )
+          ),
+          [owner]
+        );
+        await mintTo(
+          provider.connection,
+          owner,
+          otherMint.publicKey,
+          ownerOtherAta,
+          owner,
+          1000000
+        );
+
+        const first = await createTestQuest(
+          "merge-mint-target-quest",
+          new anchor.BN(500000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        );
+        const otherQuest = Keypair.generate();
+        const otherEscrow = escrowPDAFor(otherQuest.publicKey);
+        await program.methods
+          .createQuest(
+            "merge-mint-source-quest",
+            new anchor.BN(500000),
+            new anchor.BN(Date.now() / 1000 + 86400),
+            3
+          )
+          .accounts({
+            creator: owner.publicKey,
+            globalState: globalStatePDA,
+            tokenMint: otherMint.publicKey,
+            escrowAccount: otherEscrow,
+            creatorTokenAccount: ownerOtherAta,
+            quest: otherQuest.publicKey,
+            questIdRegistry: questIdRegistryPDAFor("merge-mint-source-quest"),
+            systemProgram: SystemProgram.programId,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+          })
+          .signers([owner, otherQuest])
+          .rpc();
+
+        try {
+          await mergeQuests(
+            first.quest,
+            first.escrow,
+            otherQuest.publicKey,
+            otherEscrow
+          );
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("InvalidMerge");
+        }
+      });
+
+      it("should reject a merge that overflows the winner counts", async () => {
+        const deadline = new anchor.BN(Date.now() / 1000 + 86400);
+        const first = await createTestQuest(
+          "merge-overflow-target-quest",
+          new anchor.BN(500000),
+          deadline,
+          4294967295
+        );
+        const second = await createTestQuest(
+          "merge-overflow-source-quest",
+          new anchor.BN(300000),
+          deadline,
+          1
+        );
+        try {
+          await mergeQuests(first.quest, first.escrow, second.quest, second.escrow);
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("WinnerCountOverflow");
+        }
+
+        // The target keeps its counters when the merge is rejected
+        const questAccount = await program.account.quest.fetch(first.quest);
+        expect(questAccount.maxWinners).to.equal(4294967295);
+        expect(questAccount.totalWinners).to.equal(0);
+      });
+
+      it("should not merge a frozen quest away", async () => {
+        const deadline = new anchor.BN(Date.now() / 1000 + 86400);
+        const first = await createTestQuest(
+          "merge-frozen-target-quest",
+          new anchor.BN(500000),
+          deadline,
+          3
+        );
+        const second = await createTestQuest(
+          "merge-frozen-source-quest",
+          new anchor.BN(300000),
+          deadline,
+          2
+        );
+        await program.methods
+          .ownerFreezeQuest()
+          .accounts({
+            owner: owner.publicKey,
+            globalState: globalStatePDA,
+            quest: second.quest,
+          })
+          .signers([owner])
+          .rpc();
+
+        try {
+          await mergeQuests(first.quest, first.escrow, second.quest, second.escrow);
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("QuestFrozen");
+        }
+        expect(
+          (await getAccount(provider.connection, second.escrow)).amount.toString()
+        ).to.equal("300000");
+      });
+    });
+
+    describe("set supported tokens", () => {
+      const setSupportedTokens = (mints: PublicKey[]) =>
+        program.methods
+          .setSupportedTokens(mints)
+          .accounts({ owner: owner.publicKey, globalState: globalStatePDA })
+          .signers([owner])
+          .rpc();
+
+      let original: PublicKey[];
+
+      before(async () => {
+        original = (await program.account.globalState.fetch(globalStatePDA))
+          .supportedTokenMints;
+      });
+
+      after(async () => {
+        await setSupportedTokens(original);
+      });
+
+      it("should replace the list with the deduplicated input", async () => {
+        const extraMint = Keypair.generate().publicKey;
+
+        await setSupportedTokens([
+          supportedTokenMint.publicKey,
+          extraMint,
+          supportedTokenMint.publicKey,
+        ]);
+
+        const state = await program.account.globalState.fetch(globalStatePDA);
+        expect(state.supportedTokenMints.map((pk) => pk.toString())).to.deep.equal(
+          [supportedTokenMint.publicKey.toString(), extraMint.toString()]
+        );
+      });
+
+      it("should reject the default pubkey", async () => {
+        try {
+          await setSupportedTokens([
+            supportedTokenMint.publicKey,
+            PublicKey.default,
+          ]);
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("InvalidTokenMint");
+        }
+      });
+    });
+
+    describe("quest report", () => {
+      it("should match the quest after several payouts", async () => {
+        const { quest, escrow } = await createTestQuest(
+          "report-quest",
+          new anchor.BN(500000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          5
+        );
+        for (const amount of [50000, 70000, 30000]) {
+          const { winner, winnerTokenAccount } = await createTestWinner();
+          await sendTestReward(
+            quest,
+            escrow,
+            winner.publicKey,
+            winnerTokenAccount,
+            new anchor.BN(amount)
+          );
+        }
+
+        const report = await program.methods
+          .getQuestReport()
+          .accounts({ quest })
+          .view();
+        expect(report.amount.toNumber()).to.equal(500000);
+        expect(report.distributed.toNumber()).to.equal(150000);
+        expect(report.remaining.toNumber()).to.equal(350000);
+        expect(report.winnerCount).to.equal(3);
+        expect(report.maxWinners).to.equal(5);
+        expect(report.averagePayoutInterval).to.be.null;
+      });
+
+      it("should report the payout interval once the quest completes", async () => {
+        const { quest, escrow } = await createTestQuest(
+          "payout-interval-quest",
+          new anchor.BN(500000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          2
+        );
+        const first = await createTestWinner();
+        await sendTestReward(
+          quest,
+          escrow,
+          first.winner.publicKey,
+          first.winnerTokenAccount,
+          new anchor.BN(100000)
+        );
+        const afterFirst = await program.account.quest.fetch(quest);
+        expect(afterFirst.firstRewardAt.toNumber()).to.be.greaterThan(0);
+        expect(afterFirst.completedAt.toNumber()).to.equal(0);
+
+        await new Promise((resolve) => setTimeout(resolve, 2000));
+        const second = await createTestWinner();
+        await sendTestReward(
+          quest,
+          escrow,
+          second.winner.publicKey,
+          second.winnerTokenAccount,
+          new anchor.BN(100000)
+        );
+        const afterSecond = await program.account.quest.fetch(quest);
+        // Only the first payout stamps first_reward_at
+        expect(afterSecond.firstRewardAt.toNumber()).to.equal(
+          afterFirst.firstRewardAt.toNumber()
+        );
+        expect(afterSecond.completedAt.toNumber()).to.be.greaterThan(
+          afterSecond.firstRewardAt.toNumber()
+        );
+
+        const report = await program.methods
+          .getQuestReport()
+          .accounts({ quest })
+          .view();
+        expect(report.averagePayoutInterval.toNumber()).to.equal(
+          Math.floor(
+            (afterSecond.completedAt.toNumber() -
+              afterSecond.firstRewardAt.toNumber()) /
+              2
+          )
+        );
+      });
+    });
+
+    describe("global minimum reward", () => {
+      const setMinReward = (minReward: number) =>
+        program.methods
+          .setGlobalMinReward(new anchor.BN(minReward))
+          .accounts({ owner: owner.publicKey, globalState: globalStatePDA })
+          .signers([owner])
+          .rpc();
+
+      let quest: PublicKey;
+      let escrow: PublicKey;
+
+      before(async () => {
+        ({ quest, escrow } = await createTestQuest(
+          "min-reward-quest",
+          new anchor.BN(500000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        ));
+        await setMinReward(50000);
+      });
+
+      after(async () => {
+        await setMinReward(0);
+      });
+
+      it("should reject creating a quest below the floor", async () => {
+        try {
+          await createTestQuest(
+            "sub-floor-quest",
+            new anchor.BN(40000),
+            new anchor.BN(Date.now() / 1000 + 86400),
+            3
+          );
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("BelowMinReward");
+        }
+      });
+
+      it("should reject a payout below the floor", async () => {
+        const { winner, winnerTokenAccount } = await createTestWinner();
+        try {
+          await sendTestReward(
+            quest,
+            escrow,
+            winner.publicKey,
+            winnerTokenAccount,
+            new anchor.BN(40000)
+          );
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("BelowMinReward");
+        }
+
+        await sendTestReward(
+          quest,
+          escrow,
+          winner.publicKey,
+          winnerTokenAccount,
+          new anchor.BN(50000)
+        );
+      });
+    });
+
+    describe("winner recount", () => {
+      let quest: PublicKey;
+      let winners: Keypair[];
+
+      const recountWinners = (records: Keypair[]) =>
+        program.methods
+          .recountWinners()
+          .accounts({
+            owner: owner.publicKey,
+            globalState: globalStatePDA,
+            quest,
+          })
+          .remainingAccounts(
+            records.map((winner) => ({
+              pubkey: rewardClaimedPDAFor(quest, winner.publicKey),
+              isWritable: false,
+              isSigner: false,
+            }))
+          )
+          .signers([owner])
+          .rpc();
+
+      before(async () => {
+        const created = await createTestQuest(
+          "recount-quest",
+          new anchor.BN(500000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          5
+        );
+        quest = created.quest;
+        winners = [];
+        // With no global minimum set, the last winner is paid nothing
+        for (const amount of [10000, 10000, 0]) {
+          const { winner, winnerTokenAccount } = await createTestWinner();
+          await sendTestReward(
+            quest,
+            created.escrow,
+            winner.publicKey,
+            winnerTokenAccount,
+            new anchor.BN(amount)
+          );
+          winners.push(winner);
+        }
+      });
+
+      it("should reject a partial record set", async () => {
+        try {
+          await recountWinners(winners.slice(0, 1));
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("IncompleteRewardRecords");
+        }
+        expect((await program.account.quest.fetch(quest)).totalWinners).to.equal(
+          3
+        );
+      });
+
+      it("should reject a set missing a zero-amount record", async () => {
+        try {
+          await recountWinners(winners.slice(0, 2));
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("IncompleteRewardRecords");
+        }
+        expect((await program.account.quest.fetch(quest)).totalWinners).to.equal(
+          3
+        );
+      });
+
+      it("should confirm the counter against the full record set", async () => {
+        await recountWinners(winners);
+        expect((await program.account.quest.fetch(quest)).totalWinners).to.equal(
+          3
+        );
+      });
+    });
+
+    describe("remaining claimability", () => {
+      const isClaimable = (quest: PublicKey, escrowAccount = escrowPDAFor(quest)) =>
+        program.methods
+          .isRemainingClaimable()
+          .accounts({ globalState: globalStatePDA, quest, escrowAccount })
+          .view();
+
+      const deactivate = (quest: PublicKey) =>
+        program.methods
+          .updateQuestStatus(false)
+          .accounts({
+            owner: owner.publicKey,
+            globalState: globalStatePDA,
+            quest,
+          })
+          .signers([owner])
+          .rpc();
+
+      it("should be false while the quest is active", async () => {
+        const { quest } = await createTestQuest(
+          "claimable-active-quest",
+          new anchor.BN(500000),
+          new anchor.BN(Date.now() / 1000 - 8 * 86400),
+          3
+        );
+        expect(await isClaimable(quest)).to.be.false;
+      });
+
+      it("should be false before the post-deadline delay", async () => {
+        const { quest } = await createTestQuest(
+          "claimable-early-quest",
+          new anchor.BN(500000),
+          new anchor.BN(Date.now() / 1000 - 86400),
+          3
+        );
+        await deactivate(quest);
+        expect(await isClaimable(quest)).to.be.false;
+      });
+
+      it("should be false while the quest is paused", async () => {
+        const { quest } = await createTestQuest(
+          "claimable-paused-quest",
+          new anchor.BN(500000),
+          new anchor.BN(Date.now() / 1000 - 8 * 86400),
+          3
+        );
+        await deactivate(quest);
+        await program.methods
+          .pauseQuests()
+          .accounts({ owner: owner.publicKey, globalState: globalStatePDA })
+          .remainingAccounts([
+            { pubkey: quest, isWritable: true, isSigner: false },
+          ])
+          .signers([owner])
+          .rpc();
+        expect(await isClaimable(quest)).to.be.false;
+      });
+
+      it("should be true once inactive past the delay, then false after claiming", async () => {
+        const { quest, escrow } = await createTestQuest(
+          "claimable-ready-quest",
+          new anchor.BN(500000),
+          new anchor.BN(Date.now() / 1000 - 8 * 86400),
+          3
+        );
+        await deactivate(quest);
+        expect(await isClaimable(quest)).to.be.true;
+
+        await program.methods
+          .claimRemainingReward()
+          .accounts({
+            claimer: owner.publicKey,
+            globalState: globalStatePDA,
+            quest,
+            escrowAccount: escrow,
+            creatorTokenAccount: await getAssociatedTokenAddress(
+              supportedTokenMint.publicKey,
+              owner.publicKey
+            ),
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .signers([owner])
+          .rpc();
+        expect(await isClaimable(quest)).to.be.false;
+      });
+
+      it("should be false for a cancelled quest", async () => {
+        const { quest, escrow } = await createTestQuest(
+          "claimable-cancelled-quest",
+          new anchor.BN(500000),
+          new anchor.BN(Date.now() / 1000 - 8 * 86400),
+          3
+        );
+        await program.methods
+          .cancelQuest()
+          .accounts({
+            creator: owner.publicKey,
+            globalState: globalStatePDA,
+            quest,
+            escrowAccount: escrow,
+            creatorTokenAccount: await getAssociatedTokenAddress(
+              supportedTokenMint.publicKey,
+              owner.publicKey
+            ),
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .signers([owner])
+          .rpc();
+
+        // Still owes its full amount on paper, but the escrow was refunded
+        const questAccount = await program.account.quest.fetch(quest);
+        expect(questAccount.amount.toNumber()).to.be.greaterThan(
+          questAccount.totalRewardDistributed.toNumber()
+        );
+        expect(await isClaimable(quest)).to.be.false;
+      });
+
+      it("should be false when the escrow cannot cover the remainder", async () => {
+        // Preloaded from tests/fixtures: 500000 owed, 200000 in escrow
+        expect(
+          await isClaimable(
+            new PublicKey("4qXFMLTggwQDnqTm6wSpWFiSX7eb65xy4rjjPbQjtuqP"),
+            new PublicKey("G64zGzDHcFkfif93JPYTKfDvtwwj1iEZhboQXVfHMx8k")
+          )
+        ).to.be.false;
+      });
+    });
+
+    describe("early winner bonus", () => {
+      const setBonus = (quest: PublicKey, bonusBps: number, bonusUntil: number) =>
+        program.methods
+          .setQuestBonus(bonusBps, new anchor.BN(bonusUntil))
+          .accounts({ creator: owner.publicKey, quest })
+          .signers([owner])
+          .rpc();
+
+      it("should pay the bonus while the window is open", async () => {
+        const { quest, escrow } = await createTestQuest(
+          "bonus-early-quest",
+          new anchor.BN(500000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        );
+        await setBonus(quest, 5000, Math.floor(Date.now() / 1000) + 3600);
+        const { winner, winnerTokenAccount } = await createTestWinner();
+
+        await sendTestReward(
+          quest,
+          escrow,
+          winner.publicKey,
+          winnerTokenAccount,
+          new anchor.BN(100000)
+        );
+
+        const winnerAccount = await getAccount(
+          provider.connection,
+          winnerTokenAccount
+        );
+        expect(Number(winnerAccount.amount)).to.equal(150000);
+        const questAccount = await program.account.quest.fetch(quest);
+        expect(questAccount.totalRewardDistributed.toNumber()).to.equal(150000);
+      });
+
+      it("should pay the plain amount once the window has closed", async () => {
+        const { quest, escrow } = await createTestQuest(
+          "bonus-late-quest",
+          new anchor.BN(500000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        );
+        await setBonus(quest, 5000, Math.floor(Date.now() / 1000) - 3600);
+        const { winner, winnerTokenAccount } = await createTestWinner();
+
+        await sendTestReward(
+          quest,
+          escrow,
+          winner.publicKey,
+          winnerTokenAccount,
+          new anchor.BN(100000)
+        );
+
+        const winnerAccount = await getAccount(
+          provider.connection,
+          winnerTokenAccount
+        );
+        expect(Number(winnerAccount.amount)).to.equal(100000);
+      });
+    });
+
+    describe("winner reward read", () => {
+      it("should return the record with a PDA matching client derivation", async () => {
+        const { quest, escrow } = await createTestQuest(
+          "winner-reward-quest",
+          new anchor.BN(500000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        );
+        const { winner, winnerTokenAccount } = await createTestWinner();
+        await sendTestReward(
+          quest,
+          escrow,
+          winner.publicKey,
+          winnerTokenAccount,
+          new anchor.BN(120000)
+        );
+
+        const [pda, bump] = PublicKey.findProgramAddressSync(
+          [
+            Buffer.from("reward_claimed"),
+            quest.toBuffer(),
+            winner.publicKey.toBuffer(),
+          ],
+          program.programId
+        );
+        const reward = await program.methods
+          .getWinnerReward()
+          .accounts({ rewardClaimed: pda })
+          .view();
+
+        expect(reward.pda.toString()).to.equal(pda.toString());
+        expect(reward.bump).to.equal(bump);
+        const stored = await program.account.rewardClaimed.fetch(pda);
+        expect(stored.bump).to.equal(bump);
+        expect(reward.quest.toString()).to.equal(quest.toString());
+        expect(reward.winner.toString()).to.equal(winner.publicKey.toString());
+        expect(reward.rewardAmount.toNumber()).to.equal(120000);
+        expect(reward.claimed).to.be.true;
+      });
+    });
+
+    describe("large reward approval", () => {
+      const secondApprover = Keypair.generate();
+      const setApproval = (threshold: number, approvers: PublicKey[], required: number) =>
+        program.methods
+          .setLargeRewardApproval(new anchor.BN(threshold), approvers, required)
+          .accounts({ owner: owner.publicKey, globalState: globalStatePDA })
+          .signers([owner])
+          .rpc();
+
+      const rewardApprovalPDAFor = (quest: PublicKey, winner: PublicKey) =>
+        PublicKey.findProgramAddressSync(
+          [Buffer.from("reward_approval"), quest.toBuffer(), winner.toBuffer()],
+          program.programId
+        )[0];
+
+      let quest: PublicKey;
+      let escrow: PublicKey;
+
+      before(async () => {
+        ({ quest, escrow } = await createTestQuest(
+          "approval-quest",
+          new anchor.BN(1000000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        ));
+        await setApproval(200000, [owner.publicKey, secondApprover.publicKey], 2);
+      });
+
+      after(async () => {
+        await setApproval(0, [], 0);
+      });
+
+      it("should pay rewards at or below the threshold without approval", async () => {
+        const { winner, winnerTokenAccount } = await createTestWinner();
+        await sendTestReward(
+          quest,
+          escrow,
+          winner.publicKey,
+          winnerTokenAccount,
+          new anchor.BN(200000)
+        );
+      });
+
+      it("should require enough approvals above the threshold", async () => {
+        const { winner, winnerTokenAccount } = await createTestWinner();
+        const amount = new anchor.BN(300000);
+        const rewardApproval = rewardApprovalPDAFor(quest, winner.publicKey);
+
+        try {
+          await sendTestReward(quest, escrow, winner.publicKey, winnerTokenAccount, amount);
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("LargeRewardNotApproved");
+        }
+
+        await program.methods
+          .proposeLargeReward(amount)
+          .accounts({
+            approver: owner.publicKey,
+            globalState: globalStatePDA,
+            quest,
+            winner: winner.publicKey,
+            rewardApproval,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([owner])
+          .rpc();
+
+        const sendApproved = () =>
+          program.methods
+            .sendReward(amount)
+            .accounts({
+              owner: owner.publicKey,
+              payer: owner.publicKey,
+              globalState: globalStatePDA,
+              quest,
+              escrowAccount: escrow,
+              winner: winner.publicKey,
+              winnerTokenAccount,
+              rewardClaimed: rewardClaimedPDAFor(quest, winner.publicKey),
+              tokenProgram: TOKEN_PROGRAM_ID,
+              systemProgram: SystemProgram.programId,
+              rewardApproval,
+              proposer: owner.publicKey,
+            })
+            .signers([owner])
+            .rpc();
+
+        try {
+          await sendApproved();
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("LargeRewardNotApproved");
+        }
+
+        try {
+          await program.methods
+            .approveLargeReward()
+            .accounts({ approver: owner.publicKey, globalState: globalStatePDA, rewardApproval })
+            .signers([owner])
+            .rpc();
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("AlreadyApproved");
+        }
+
+        await program.methods
+          .approveLargeReward()
+          .accounts({
+            approver: secondApprover.publicKey,
+            globalState: globalStatePDA,
+            rewardApproval,
+          })
+          .signers([secondApprover])
+          .rpc();
+
+        await sendApproved();
+
+        const winnerAccount = await getAccount(provider.connection, winnerTokenAccount);
+        expect(Number(winnerAccount.amount)).to.equal(300000);
+        expect(await provider.connection.getAccountInfo(rewardApproval)).to.be.null;
+      });
+
+      it("should reject proposals from non-approvers", async () => {
+        const { winner: outsider } = await createTestWinner();
+        const { winner } = await createTestWinner();
+        try {
+          await program.methods
+            .proposeLargeReward(new anchor.BN(300000))
+            .accounts({
+              approver: outsider.publicKey,
+              globalState: globalStatePDA,
+              quest,
+              winner: winner.publicKey,
+              rewardApproval: rewardApprovalPDAFor(quest, winner.publicKey),
+              systemProgram: SystemProgram.programId,
+            })
+            .signers([outsider])
+            .rpc();
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("UnauthorizedApprover");
+        }
+      });
+
+      it("should reject proposals at or below the threshold", async () => {
+        const { winner } = await createTestWinner();
+        try {
+          await program.methods
+            .proposeLargeReward(new anchor.BN(200000))
+            .accounts({
+              approver: owner.publicKey,
+              globalState: globalStatePDA,
+              quest,
+              winner: winner.publicKey,
+              rewardApproval: rewardApprovalPDAFor(quest, winner.publicKey),
+              systemProgram: SystemProgram.programId,
+            })
+            .signers([owner])
+            .rpc();
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("BelowApprovalThreshold");
+        }
+      });
+
+      it("should let an approver cancel a wrong proposal and propose again", async () => {
+        const { winner, winnerTokenAccount } = await createTestWinner();
+        const rewardApproval = rewardApprovalPDAFor(quest, winner.publicKey);
+        const propose = (amount: number) =>
+          program.methods
+            .proposeLargeReward(new anchor.BN(amount))
+            .accounts({
+              approver: owner.publicKey,
+              globalState: globalStatePDA,
+              quest,
+              winner: winner.publicKey,
+              rewardApproval,
+              systemProgram: SystemProgram.programId,
+            })
+            .signers([owner])
+            .rpc();
+
+        await propose(900000);
+        await program.methods
+          .cancelLargeReward()
+          .accounts({
+            canceller: secondApprover.publicKey,
+            globalState: globalStatePDA,
+            rewardApproval,
+            proposer: owner.publicKey,
+          })
+          .signers([secondApprover])
+          .rpc();
+        expect(await provider.connection.getAccountInfo(rewardApproval)).to.be.null;
+
+        await propose(250000);
+        await program.methods
+          .approveLargeReward()
+          .accounts({
+            approver: secondApprover.publicKey,
+            globalState: globalStatePDA,
+            rewardApproval,
+          })
+          .signers([secondApprover])
+          .rpc();
+        await program.methods
+          .sendReward(new anchor.BN(250000))
+          .accounts({
+            owner: owner.publicKey,
+            payer: owner.publicKey,
+            globalState: globalStatePDA,
+            quest,
+            escrowAccount: escrow,
+            winner: winner.publicKey,
+            winnerTokenAccount,
+            rewardClaimed: rewardClaimedPDAFor(quest, winner.publicKey),
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+            rewardApproval,
+            proposer: owner.publicKey,
+          })
+          .signers([owner])
+          .rpc();
+
+        const winnerAccount = await getAccount(provider.connection, winnerTokenAccount);
+        expect(Number(winnerAccount.amount)).to.equal(250000);
+      });
+
+      it("should return the approval's rent to its proposer", async () => {
+        const signature = await provider.connection.requestAirdrop(
+          secondApprover.publicKey,
+          anchor.web3.LAMPORTS_PER_SOL
+        );
+        await provider.connection.confirmTransaction(signature);
+        const { quest, escrow } = await createTestQuest(
+          "approval-proposer-quest",
+          new anchor.BN(1000000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        );
+        const { winner, winnerTokenAccount } = await createTestWinner();
+        const rewardApproval = rewardApprovalPDAFor(quest, winner.publicKey);
+        await program.methods
+          .proposeLargeReward(new anchor.BN(250000))
+          .accounts({
+            approver: secondApprover.publicKey,
+            globalState: globalStatePDA,
+            quest,
+            winner: winner.publicKey,
+            rewardApproval,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([secondApprover])
+          .rpc();
+        await program.methods
+          .approveLargeReward()
+          .accounts({ approver: owner.publicKey, globalState: globalStatePDA, rewardApproval })
+          .signers([owner])
+          .rpc();
+
+        const sendApproved = (proposer: PublicKey) =>
+          program.methods
+            .sendReward(new anchor.BN(250000))
+            .accounts({
+              owner: owner.publicKey,
+              payer: owner.publicKey,
+              globalState: globalStatePDA,
+              quest,
+              escrowAccount: escrow,
+              winner: winner.publicKey,
+              winnerTokenAccount,
+              rewardClaimed: rewardClaimedPDAFor(quest, winner.publicKey),
+              tokenProgram: TOKEN_PROGRAM_ID,
+              systemProgram: SystemProgram.programId,
+              rewardApproval,
+              proposer,
+            })
+            .signers([owner])
+            .rpc();
+
+        try {
+          await sendApproved(owner.publicKey);
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("InvalidRentRecipient");
+        }
+
+        const rent = await provider.connection.getBalance(rewardApproval);
+        const proposerLamportsBefore = await provider.connection.getBalance(
+          secondApprover.publicKey
+        );
+        await sendApproved(secondApprover.publicKey);
+        expect(await provider.connection.getBalance(secondApprover.publicKey)).to.equal(
+          proposerLamportsBefore + rent
+        );
+      });
+    });
+
+    describe("spoofed global state", () => {
+      // Program-owned GlobalState preloaded from tests/fixtures at a non-PDA address
+      const spoofedGlobalState = new PublicKey(
+        "E71sVHLNDbKfM8TcGtVJKNwpTojwZuvH273dSQHnQBaU"
+      );
+      // Recorded as the owner inside the spoofed account
+      const spoofedOwner = Keypair.fromSecretKey(
+        Uint8Array.from(
+          JSON.parse(
+            readFileSync("tests/fixtures/spoofed-global-state-owner.json", "utf8")
+          )
+        )
+      );
+
+      const expectSeedsRejection = async (tx: Promise<string>) => {
+        try {
+          await tx;
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("ConstraintSeeds");
+        }
+      };
+
+      before(async () => {
+        const signature = await provider.connection.requestAirdrop(
+          spoofedOwner.publicKey,
+          anchor.web3.LAMPORTS_PER_SOL
+        );
+        await provider.connection.confirmTransaction(signature);
+      });
+
+      it("should reject create_quest with a non-PDA global state", async () => {
+        const questKeypair = Keypair.generate();
+        try {
+          await program.methods
+            .createQuest(
+              "spoofed-state-quest",
+              new anchor.BN(500000),
+              new anchor.BN(Date.now() / 1000 + 86400),
+              3
+            )
+            .accounts({
+              creator: owner.publicKey,
+              globalState: spoofedGlobalState,
+              tokenMint: supportedTokenMint.publicKey,
+              escrowAccount: escrowPDAFor(questKeypair.publicKey),
+              creatorTokenAccount: await getAssociatedTokenAddress(
+                supportedTokenMint.publicKey,
+                owner.publicKey
+              ),
+              quest: questKeypair.publicKey,
+              questIdRegistry: questIdRegistryPDAFor("spoofed-state-quest"),
+              systemProgram: SystemProgram.programId,
+              tokenProgram: TOKEN_PROGRAM_ID,
+              rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+            })
+            .signers([owner, questKeypair])
+            .rpc();
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("ConstraintSeeds");
+        }
+      });
+
+      it("should reject update_quest_status with a non-PDA global state", async () => {
+        const { quest } = await createTestQuest(
+          "spoofed-status-quest",
+          new anchor.BN(500000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        );
+        await expectSeedsRejection(
+          program.methods
+            .updateQuestStatus(false)
+            .accounts({
+              owner: spoofedOwner.publicKey,
+              globalState: spoofedGlobalState,
+              quest,
+            })
+            .signers([spoofedOwner])
+            .rpc()
+        );
+      });
+
+      it("should reject token list changes with a non-PDA global state", async () => {
+        await expectSeedsRejection(
+          program.methods
+            .addSupportedToken()
+            .accounts({
+              owner: spoofedOwner.publicKey,
+              globalState: spoofedGlobalState,
+              tokenMint: supportedTokenMint.publicKey,
+            })
+            .signers([spoofedOwner])
+            .rpc()
+        );
+        await expectSeedsRejection(
+          program.methods
+            .removeSupportedToken()
+            .accounts({
+              owner: spoofedOwner.publicKey,
+              globalState: spoofedGlobalState,
+              tokenMint: supportedTokenMint.publicKey,
+            })
+            .signers([spoofedOwner])
+            .rpc()
+        );
+      });
+
+      it("should reject pause and unpause with a non-PDA global state", async () => {
+        await expectSeedsRejection(
+          program.methods
+            .pause()
+            .accounts({
+              owner: spoofedOwner.publicKey,
+              globalState: spoofedGlobalState,
+            })
+            .signers([spoofedOwner])
+            .rpc()
+        );
+        await expectSeedsRejection(
+          program.methods
+            .unpause()
+            .accounts({
+              owner: spoofedOwner.publicKey,
+              globalState: spoofedGlobalState,
+            })
+            .signers([spoofedOwner])
+            .rpc()
+        );
+      });
+    });
+
+    describe("quest count recount", () => {
+      const recount = (quests: PublicKey[], reset: boolean) =>
+        program.methods
+          .recountQuests(reset)
+          .accounts({ owner: owner.publicKey, globalState: globalStatePDA })
+          .remainingAccounts(
+            quests.map((pubkey) => ({ pubkey, isWritable: false, isSigner: false }))
+          )
+          .signers([owner])
+          .rpc();
+
+      it("should repair a drifted quest count", async () => {
+        const quests = (await program.account.quest.all()).map(
+          ({ publicKey }) => publicKey
+        );
+
+        // Drift the counter by recounting a single quest
+        await recount(quests.slice(0, 1), true);
+        let state = await program.account.globalState.fetch(globalStatePDA);
+        expect(state.questCount).to.equal(1);
+
+        // Recount every quest in transaction-sized chunks
+        for (let i = 0; i < quests.length; i += 20) {
+          await recount(quests.slice(i, i + 20), i === 0);
+        }
+        state = await program.account.globalState.fetch(globalStatePDA);
+        expect(state.questCount).to.equal(quests.length);
+      });
+
+      it("should reject accounts that are not quests", async () => {
+        try {
+          await recount([globalStatePDA], true);
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("AccountDiscriminatorMismatch");
+        }
+      });
+
+      it("should reject a quest listed twice", async () => {
+        const { quest } = await createTestQuest(
+          "recount-duplicate-quest",
+          new anchor.BN(500000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        );
+        try {
+          await recount([quest, quest], false);
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("DuplicateQuestAccount");
+        }
+      });
+    });
+
+    describe("program-owned winners", () => {
+      it("should pay a token account owned by a PDA winner", async () => {
+        const { quest, escrow } = await createTestQuest(
+          "pda-winner-quest",
+          new anchor.BN(500000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        );
+        // Stand-in for another program's vault authority; it has no private key
+        const [vault] = PublicKey.findProgramAddressSync(
+          [Buffer.from("vault"), quest.toBuffer()],
+          SystemProgram.programId
+        );
+        const vaultTokenAccount = await getAssociatedTokenAddress(
+          supportedTokenMint.publicKey,
+          vault,
+          true
+        );
+        await provider.sendAndConfirm(
+          new Transaction().add(
+            createAssociatedTokenAccountInstruction(
+              owner.publicKey,
+              vaultTokenAccount,
+              vault,
+              supportedTokenMint.publicKey
+            )
+          ),
+          [owner]
+        );
+
+        await sendTestReward(
+          quest,
+          escrow,
+          vault,
+          vaultTokenAccount,
+          new anchor.BN(100000)
+        );
+
+        const vaultAccount = await getAccount(provider.connection, vaultTokenAccount);
+        expect(Number(vaultAccount.amount)).to.equal(100000);
+        const record = await program.account.rewardClaimed.fetch(
+          rewardClaimedPDAFor(quest, vault)
+        );
+        expect(record.winner.toString()).to.equal(vault.toString());
+      });
+
+      it("should reject a PDA-owned token account paid to a different winner", async () => {
+        const { quest, escrow } = await createTestQuest(
+          "pda-winner-mismatch-quest",
+          new anchor.BN(500000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        );
+        const [vault] = PublicKey.findProgramAddressSync(
+          [Buffer.from("vault"), quest.toBuffer()],
+          SystemProgram.programId
+        );
+        const vaultTokenAccount = await getAssociatedTokenAddress(
+          supportedTokenMint.publicKey,
+          vault,
+          true
+        );
+        await provider.sendAndConfirm(
+          new Transaction().add(
+            createAssociatedTokenAccountInstruction(
+              owner.publicKey,
+              vaultTokenAccount,
+              vault,
+              supportedTokenMint.publicKey
+            )
+          ),
+          [owner]
+        );
+
+        const { winner } = await createTestWinner();
+        try {
+          await sendTestReward(
+            quest,
+            escrow,
+            winner.publicKey,
+            vaultTokenAccount,
+            new anchor.BN(100000)
+          );
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("ConstraintRaw");
+        }
+      });
+    });
+
+    describe("deadline bounds", () => {
+      it("should reject a negative deadline", async () => {
+        try {
+          await createTestQuest(
+            "negative-deadline-quest",
+            new anchor.BN(500000),
+            new anchor.BN(-86400),
+            3
+          );
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("InvalidDeadline");
+        }
+      });
+
+      it("should reject a deadline more than 100 years out", async () => {
+        for (const deadline of [
+          new anchor.BN(Date.now() / 1000 + 101 * 365 * 86400),
+          new anchor.BN("9223372036854775807"),
+        ]) {
+          try {
+            await createTestQuest("far-deadline-quest", new anchor.BN(500000), deadline, 3);
+            expect.fail("Expected the transaction to fail");
+          } catch (error) {
+            expect(error.error.errorCode.code).to.equal("InvalidDeadline");
+          }
+        }
+      });
+
+      it("should accept a deadline within the horizon", async () => {
+        const deadline = new anchor.BN(Date.now() / 1000 + 99 * 365 * 86400);
+        const { quest } = await createTestQuest(
+          "long-deadline-quest",
+          new anchor.BN(500000),
+          deadline,
+          3
+        );
+        const questAccount = await program.account.quest.fetch(quest);
+        expect(questAccount.deadline.toNumber()).to.equal(deadline.toNumber());
+      });
+    });
+
+    describe("claims pause", () => {
+      const setClaimsPaused = (paused: boolean) =>
+        (paused ? program.methods.pauseClaims() : program.methods.unpauseClaims())
+          .accounts({ owner: owner.publicKey, globalState: globalStatePDA })
+          .signers([owner])
+          .rpc();
+
+      let quest: PublicKey;
+      let escrow: PublicKey;
+
+      before(async () => {
+        ({ quest, escrow } = await createTestQuest(
+          "claims-pause-quest",
+          new anchor.BN(500000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        ));
+        await setClaimsPaused(true);
+      });
+
+      after(async () => {
+        await setClaimsPaused(false);
+      });
+
+      it("should block payouts while claims are paused", async () => {
+        const { winner, winnerTokenAccount } = await createTestWinner();
+        try {
+          await sendTestReward(
+            quest,
+            escrow,
+            winner.publicKey,
+            winnerTokenAccount,
+            new anchor.BN(100000)
+          );
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("ClaimsPaused");
+        }
+      });
+
+      it("should block remaining reward claims while claims are paused", async () => {
+        const expired = await createTestQuest(
+          "claims-pause-expired-quest",
+          new anchor.BN(500000),
+          new anchor.BN(Date.now() / 1000 - 8 * 86400),
+          3
+        );
+        await program.methods
+          .updateQuestStatus(false)
+          .accounts({
+            owner: owner.publicKey,
+            globalState: globalStatePDA,
+            quest: expired.quest,
+          })
+          .signers([owner])
+          .rpc();
+
+        try {
+          await program.methods
+            .claimRemainingReward()
+            .accounts({
+              claimer: owner.publicKey,
+              globalState: globalStatePDA,
+              quest: expired.quest,
+              escrowAccount: expired.escrow,
+              creatorTokenAccount: await getAssociatedTokenAddress(
+                supportedTokenMint.publicKey,
+                owner.publicKey
+              ),
+              tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .signers([owner])
+            .rpc();
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("ClaimsPaused");
+        }
+      });
+
+      it("should keep quest creation open while claims are paused", async () => {
+        const { quest: created } = await createTestQuest(
+          "claims-pause-created-quest",
+          new anchor.BN(500000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        );
+        const questAccount = await program.account.quest.fetch(created);
+        expect(questAccount.isActive).to.be.true;
+
+        const config = await program.methods
+          .getConfig()
+          .accounts({ globalState: globalStatePDA })
+          .view();
+        expect(config.claimsPaused).to.be.true;
+      });
+    });
+
+    describe("pause status", () => {
+      const pauseStatus = () =>
+        program.methods
+          .getPauseStatus()
+          .accounts({ globalState: globalStatePDA })
+          .view();
+
+      it("should aggregate the individual pause flags", async () => {
+        let status = await pauseStatus();
+        expect(status.paused).to.be.false;
+        expect(status.claimsPaused).to.be.false;
+        expect(status.anyPaused).to.be.false;
+
+        await program.methods
+          .pauseClaims()
+          .accounts({ owner: owner.publicKey, globalState: globalStatePDA })
+          .signers([owner])
+          .rpc();
+        try {
+          status = await pauseStatus();
+          expect(status.paused).to.be.false;
+          expect(status.claimsPaused).to.be.true;
+          expect(status.anyPaused).to.be.true;
+        } finally {
+          await program.methods
+            .unpauseClaims()
+            .accounts({ owner: owner.publicKey, globalState: globalStatePDA })
+            .signers([owner])
+            .rpc();
+        }
+
+        await program.methods
+          .pause()
+          .accounts({ owner: owner.publicKey, globalState: globalStatePDA })
+          .signers([owner])
+          .rpc();
+        try {
+          status = await pauseStatus();
+          expect(status.paused).to.be.true;
+          expect(status.claimsPaused).to.be.false;
+          expect(status.anyPaused).to.be.true;
+        } finally {
+          await program.methods
+            .unpause()
+            .accounts({ owner: owner.publicKey, globalState: globalStatePDA })
+            .signers([owner])
+            .rpc();
+        }
+      });
+    });
+
+    describe("remaining reward recipient", () => {
+      // Creates an expired, deactivated quest funded by `creator`
+      const createExpiredQuest = async (id: string, creator: Keypair) => {
+        const questKeypair = Keypair.generate();
+        const escrow = escrowPDAFor(questKeypair.publicKey);
+        const creatorTokenAccount = await getAssociatedTokenAddress(
+          supportedTokenMint.publicKey,
+          creator.publicKey
+        );
+        await program.methods
+          .createQuest(
+            id,
+            new anchor.BN(400000),
+            new anchor.BN(Date.now() / 1000 - 8 * 86400),
+            3
+          )
+          .accounts({
+            creator: creator.publicKey,
+            globalState: globalStatePDA,
+            tokenMint: supportedTokenMint.publicKey,
+            escrowAccount: escrow,
+            creatorTokenAccount,
+            quest: questKeypair.publicKey,
+            questIdRegistry: questIdRegistryPDAFor(id),
+            systemProgram: SystemProgram.programId,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+          })
+          .signers([creator, questKeypair])
+          .rpc();
+        await program.methods
+          .updateQuestStatus(false)
+          .accounts({
+            owner: owner.publicKey,
+            globalState: globalStatePDA,
+            quest: questKeypair.publicKey,
+          })
+          .signers([owner])
+          .rpc();
+        return { quest: questKeypair.publicKey, escrow, creatorTokenAccount };
+      };
+
+      it("should pay a creator-chosen recipient", async () => {
+        const { quest, escrow, creatorTokenAccount } = await createExpiredQuest(
+          "redirected-remaining-quest",
+          owner
+        );
+        const { winnerTokenAccount: recipientTokenAccount } = await createTestWinner();
+        const creatorBefore = (await getAccount(provider.connection, creatorTokenAccount))
+          .amount;
+
+        await program.methods
+          .claimRemainingReward()
+          .accounts({
+            claimer: owner.publicKey,
+            globalState: globalStatePDA,
+            quest,
+            escrowAccount: escrow,
+            creatorTokenAccount,
+            recipientTokenAccount,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .signers([owner])
+          .rpc();
+
+        const recipient = await getAccount(provider.connection, recipientTokenAccount);
+        expect(Number(recipient.amount)).to.equal(400000);
+        const creatorAfter = (await getAccount(provider.connection, creatorTokenAccount))
+          .amount;
+        expect(creatorAfter).to.equal(creatorBefore);
+      });
+
+      it("should default to the creator's account and not let the owner redirect", async () => {
+        const { winner: creator, winnerTokenAccount } = await createTestWinner();
+        await mintTo(
+          provider.connection,
+          owner,
+          supportedTokenMint.publicKey,
+          winnerTokenAccount,
+          owner,
+          400000
+        );
+        const { quest, escrow, creatorTokenAccount } = await createExpiredQuest(
+          "owner-remaining-quest",
+          creator
+        );
+        const { winnerTokenAccount: recipientTokenAccount } = await createTestWinner();
+
+        try {
+          await program.methods
+            .claimRemainingReward()
+            .accounts({
+              claimer: owner.publicKey,
+              globalState: globalStatePDA,
+              quest,
+              escrowAccount: escrow,
+              creatorTokenAccount,
+              recipientTokenAccount,
+              tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .signers([owner])
+            .rpc();
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("UnauthorizedRecipient");
+        }
+
+        await program.methods
+          .claimRemainingReward()
+          .accounts({
+            claimer: owner.publicKey,
+            globalState: globalStatePDA,
+            quest,
+            escrowAccount: escrow,
+            creatorTokenAccount,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .signers([owner])
+          .rpc();
+
+        const creatorAccount = await getAccount(provider.connection, creatorTokenAccount);
+        expect(Number(creatorAccount.amount)).to.equal(400000);
+      });
+    });
+
+    describe("quest id registry", () => {
+      it("should record the quest that reserved an id", async () => {
+        const { quest } = await createTestQuest(
+          "registered-id-quest",
+          new anchor.BN(500000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        );
+        const registry = await program.account.questIdRegistry.fetch(
+          questIdRegistryPDAFor("registered-id-quest")
+        );
+        expect(registry.quest.toString()).to.equal(quest.toString());
+      });
+
+      it("should reject a second quest with the same id", async () => {
+        await createTestQuest(
+          "duplicate-id-quest",
+          new anchor.BN(500000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        );
+        try {
+          await createTestQuest(
+            "duplicate-id-quest",
+            new anchor.BN(500000),
+            new anchor.BN(Date.now() / 1000 + 86400),
+            3
+          );
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.toString()).to.include("already in use");
+        }
+      });
+    });
+
+    describe("remaining reward escrow balance", () => {
+      const claimRemaining = (
+        claimer: Keypair,
+        quest: PublicKey,
+        escrow: PublicKey,
+        creatorTokenAccount: PublicKey
+      ) =>
+        program.methods
+          .claimRemainingReward()
+          .accounts({
+            claimer: claimer.publicKey,
+            globalState: globalStatePDA,
+            quest,
+            escrowAccount: escrow,
+            creatorTokenAccount,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .signers([claimer])
+          .rpc();
+
+      it("should reject a claim the escrow cannot cover", async () => {
+        // Preloaded from tests/fixtures: 500000 owed, 200000 in escrow
+        const quest = new PublicKey("4qXFMLTggwQDnqTm6wSpWFiSX7eb65xy4rjjPbQjtuqP");
+        const escrow = new PublicKey("G64zGzDHcFkfif93JPYTKfDvtwwj1iEZhboQXVfHMx8k");
+        const creatorTokenAccount = new PublicKey(
+          "5Lo2KvetSaF79cimz3zaUZVcj7W86RBVpRmLA8zV3BkB"
+        );
+        const creator = Keypair.fromSecretKey(
+          Uint8Array.from(
+            JSON.parse(
+              readFileSync("tests/fixtures/underfunded-quest-creator.json", "utf8")
+            )
+          )
+        );
+        const signature = await provider.connection.requestAirdrop(
+          creator.publicKey,
+          anchor.web3.LAMPORTS_PER_SOL
+        );
+        await provider.connection.confirmTransaction(signature);
+
+        try {
+          await claimRemaining(creator, quest, escrow, creatorTokenAccount);
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("InsufficientRewardBalance");
+        }
+
+        const questAccount = await program.account.quest.fetch(quest);
+        expect(questAccount.finalized).to.be.false;
+        expect(questAccount.amount.toNumber()).to.equal(500000);
+      });
+
+      it("should drain a healthy escrow exactly", async () => {
+        const { quest, escrow } = await createTestQuest(
+          "healthy-escrow-quest",
+          new anchor.BN(400000),
+          new anchor.BN(Date.now() / 1000 - 8 * 86400),
+          3
+        );
+        await program.methods
+          .updateQuestStatus(false)
+          .accounts({
+            owner: owner.publicKey,
+            globalState: globalStatePDA,
+            quest,
+          })
+          .signers([owner])
+          .rpc();
+
+        await claimRemaining(
+          owner,
+          quest,
+          escrow,
+          await getAssociatedTokenAddress(supportedTokenMint.publicKey, owner.publicKey)
+        );
+
+        const escrowAccount = await getAccount(provider.connection, escrow);
+        expect(Number(escrowAccount.amount)).to.equal(0);
+      });
+    });
+
+    describe("leftover policy", () => {
+      // Creates an expired, deactivated quest funded by the owner
+      const createExpiredQuest = async (id: string) => {
+        const questKeypair = Keypair.generate();
+        const escrow = escrowPDAFor(questKeypair.publicKey);
+        const creatorTokenAccount = await getAssociatedTokenAddress(
+          supportedTokenMint.publicKey,
+          owner.publicKey
+        );
+        await program.methods
+          .createQuest(id, new anchor.BN(300000), new anchor.BN(Date.now() / 1000 - 8 * 86400), 3)
+          .accounts({
+            creator: owner.publicKey,
+            globalState: globalStatePDA,
+            tokenMint: supportedTokenMint.publicKey,
+            escrowAccount: escrow,
+            creatorTokenAccount,
+            quest: questKeypair.publicKey,
+            questIdRegistry: questIdRegistryPDAFor(id),
+            systemProgram: SystemProgram.programId,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+          })
+          .signers([owner, questKeypair])
+          .rpc();
+        await program.methods
+          .updateQuestStatus(false)
+          .accounts({
+            owner: owner.publicKey,
+            globalState: globalStatePDA,
+            quest: questKeypair.publicKey,
+          })
+          .signers([owner])
+          .rpc();
+        return { quest: questKeypair.publicKey, escrow, creatorTokenAccount };
+      };
+
+      it("should refund leftover funds by default", async () => {
+        const { quest, escrow, creatorTokenAccount } = await createExpiredQuest(
+          "refund-policy-quest"
+        );
+        const questAccount = await program.account.quest.fetch(quest);
+        expect(questAccount.leftoverPolicy).to.deep.equal({ refund: {} });
+        const creatorBefore = (await getAccount(provider.connection, creatorTokenAccount))
+          .amount;
+
+        await program.methods
+          .claimRemainingReward()
+          .accounts({
+            claimer: owner.publicKey,
+            globalState: globalStatePDA,
+            quest,
+            escrowAccount: escrow,
+            creatorTokenAccount,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .signers([owner])
+          .rpc();
+
+        const creatorAfter = (await getAccount(provider.connection, creatorTokenAccount))
+          .amount;
+        expect(Number(creatorAfter - creatorBefore)).to.equal(300000);
+      });
+
+      it("should burn leftover funds when the quest asks for it", async () => {
+        const { quest, escrow, creatorTokenAccount } = await createExpiredQuest(
+          "burn-policy-quest"
+        );
+        await program.methods
+          .setLeftoverPolicy({ burn: {} })
+          .accounts({ creator: owner.publicKey, quest })
+          .signers([owner])
+          .rpc();
+
+        // Burning needs the mint account
+        try {
+          await program.methods
+            .claimRemainingReward()
+            .accounts({
+              claimer: owner.publicKey,
+              globalState: globalStatePDA,
+              quest,
+              escrowAccount: escrow,
+              creatorTokenAccount,
+              tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .signers([owner])
+            .rpc();
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("InvalidTokenMint");
+        }
+
+        const supplyBefore = (await getMint(provider.connection, supportedTokenMint.publicKey))
+          .supply;
+        const creatorBefore = (await getAccount(provider.connection, creatorTokenAccount))
+          .amount;
+
+        const tx = await program.methods
+          .claimRemainingReward()
+          .accounts({
+            claimer: owner.publicKey,
+            globalState: globalStatePDA,
+            quest,
+            escrowAccount: escrow,
+            creatorTokenAccount,
+            tokenMint: supportedTokenMint.publicKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .signers([owner])
+          .rpc();
+
+        const supplyAfter = (await getMint(provider.connection, supportedTokenMint.publicKey))
+          .supply;
+        expect(Number(supplyBefore - supplyAfter)).to.equal(300000);
+        const creatorAfter = (await getAccount(provider.connection, creatorTokenAccount))
+          .amount;
+        expect(creatorAfter).to.equal(creatorBefore);
+        expect(Number((await getAccount(provider.connection, escrow)).amount)).to.equal(0);
+
+        const event = (await eventsFromTx(tx)).find(
+          (e) => e.name === "remainingRewardClaimed"
+        );
+        expect(event.data.burned).to.equal(true);
+      });
+
+      it("should not let anyone but the creator change the policy", async () => {
+        const { quest } = await createExpiredQuest("burn-policy-unauthorized-quest");
+        const { winner: stranger } = await createTestWinner();
+        try {
+          await program.methods
+            .setLeftoverPolicy({ burn: {} })
+            .accounts({ creator: stranger.publicKey, quest })
+            .signers([stranger])
+            .rpc();
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("UnauthorizedQuestUpdate");
+        }
+      });
+    });
+
+    describe("quest health check", () => {
+      const healthCheck = (quest: PublicKey, escrowAccount: PublicKey) =>
+        program.methods.questHealthCheck().accounts({ quest, escrowAccount }).view();
+
+      it("should report a freshly created quest as healthy", async () => {
+        const { quest, escrow } = await createTestQuest(
+          "health-check-quest",
+          new anchor.BN(300000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        );
+        const report = await healthCheck(quest, escrow);
+        expect(report.distributedWithinAmount).to.be.true;
+        expect(report.winnersWithinMax).to.be.true;
+        expect(report.escrowSolvent).to.be.true;
+        expect(report.escrowLinked).to.be.true;
+        expect(report.healthy).to.be.true;
+      });
+
+      it("should flag an escrow that does not belong to the quest", async () => {
+        const { quest } = await createTestQuest(
+          "health-check-unlinked-quest",
+          new anchor.BN(300000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        );
+        const { escrow: otherEscrow } = await createTestQuest(
+          "health-check-other-quest",
+          new anchor.BN(300000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        );
+        const report = await healthCheck(quest, otherEscrow);
+        expect(report.escrowLinked).to.be.false;
+        expect(report.healthy).to.be.false;
+      });
+
+      it("should flag an escrow holding less than the quest owes", async () => {
+        // Preloaded from tests/fixtures: 500000 owed, 200000 in escrow
+        const report = await healthCheck(
+          new PublicKey("4qXFMLTggwQDnqTm6wSpWFiSX7eb65xy4rjjPbQjtuqP"),
+          new PublicKey("G64zGzDHcFkfif93JPYTKfDvtwwj1iEZhboQXVfHMx8k")
+        );
+        expect(report.escrowSolvent).to.be.false;
+        expect(report.escrowLinked).to.be.true;
+        expect(report.healthy).to.be.false;
+      });
+
+      it("should flag overpaid and overfilled quests", async () => {
+        // Preloaded from tests/fixtures: 600000 of 500000 paid to 4 of 3 winners
+        const report = await healthCheck(
+          new PublicKey("8H7YEFoubY5bo7U2CQd4j3EHyb58m16PTHsyhMm4P5wC"),
+          new PublicKey("G64zGzDHcFkfif93JPYTKfDvtwwj1iEZhboQXVfHMx8k")
+        );
+        expect(report.distributedWithinAmount).to.be.false;
+        expect(report.winnersWithinMax).to.be.false;
+        expect(report.healthy).to.be.false;
+      });
+    });
+
+    describe("reward sent event", () => {
+      it("should report the remaining amount and slots after each send", async () => {
+        const { quest, escrow } = await createTestQuest(
+          "reward-sent-event-quest",
+          new anchor.BN(500000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        );
+        const rewardSentFor = async (rewardAmount: number) => {
+          const { winner, winnerTokenAccount } = await createTestWinner();
+          const signature = await sendTestReward(
+            quest,
+            escrow,
+            winner.publicKey,
+            winnerTokenAccount,
+            new anchor.BN(rewardAmount)
+          );
+          const event = (await eventsFromTx(signature)).find(
+            (e) => e.name === "rewardSent"
+          );
+          expect(event.data.winner.toString()).to.equal(winner.publicKey.toString());
+          return event.data;
+        };
+
+        const first = await rewardSentFor(100000);
+        expect(first.quest.toString()).to.equal(quest.toString());
+        expect(first.amount.toNumber()).to.equal(100000);
+        expect(first.remainingAmount.toNumber()).to.equal(400000);
+        expect(first.remainingSlots).to.equal(2);
+
+        const second = await rewardSentFor(150000);
+        expect(second.remainingAmount.toNumber()).to.equal(250000);
+        expect(second.remainingSlots).to.equal(1);
+      });
+    });
+
+    describe("transfer between quests", () => {
+      const transferBetween = (
+        from: { quest: PublicKey; escrow: PublicKey },
+        to: { quest: PublicKey; escrow: PublicKey },
+        amount: number,
+        creator: Keypair = owner
+      ) =>
+        program.methods
+          .transferBetweenQuests(new anchor.BN(amount))
+          .accounts({
+            creator: creator.publicKey,
+            globalState: globalStatePDA,
+            quest: to.quest,
+            escrowAccount: to.escrow,
+            sourceQuest: from.quest,
+            sourceEscrowAccount: from.escrow,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .signers([creator])
+          .rpc();
+
+      it("should move funds and adjust both quests' amounts", async () => {
+        const source = await createTestQuest(
+          "transfer-source-quest",
+          new anchor.BN(500000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        );
+        const target = await createTestQuest(
+          "transfer-target-quest",
+          new anchor.BN(200000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        );
+        const { winner, winnerTokenAccount } = await createTestWinner();
+        await sendTestReward(
+          source.quest,
+          source.escrow,
+          winner.publicKey,
+          winnerTokenAccount,
+          new anchor.BN(100000)
+        );
+
+        await transferBetween(source, target, 150000);
+
+        const sourceQuest = await program.account.quest.fetch(source.quest);
+        expect(sourceQuest.amount.toNumber()).to.equal(350000);
+        expect(sourceQuest.totalRewardDistributed.toNumber()).to.equal(100000);
+        const targetQuest = await program.account.quest.fetch(target.quest);
+        expect(targetQuest.amount.toNumber()).to.equal(350000);
+        expect(targetQuest.totalRewardDistributed.toNumber()).to.equal(0);
+        expect(
+          Number((await getAccount(provider.connection, source.escrow)).amount)
+        ).to.equal(250000);
+        expect(
+          Number((await getAccount(provider.connection, target.escrow)).amount)
+        ).to.equal(350000);
+
+        // Already-distributed funds cannot be moved
+        try {
+          await transferBetween(source, target, 300000);
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("InsufficientRewardBalance");
+        }
+      });
+
+      it("should reject quests belonging to another creator", async () => {
+        const source = await createTestQuest(
+          "transfer-foreign-source-quest",
+          new anchor.BN(300000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        );
+        const target = await createTestQuest(
+          "transfer-foreign-target-quest",
+          new anchor.BN(300000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        );
+        const { winner: stranger } = await createTestWinner();
+        try {
+          await transferBetween(source, target, 100000, stranger);
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("UnauthorizedQuestUpdate");
+        }
+      });
+
+      it("should not move funds out of a frozen quest", async () => {
+        const source = await createTestQuest(
+          "transfer-frozen-source-quest",
+          new anchor.BN(500000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        );
+        const target = await createTestQuest(
+          "transfer-frozen-target-quest",
+          new anchor.BN(200000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        );
+        await program.methods
+          .ownerFreezeQuest()
+          .accounts({
+            owner: owner.publicKey,
+            globalState: globalStatePDA,
+            quest: source.quest,
+          })
+          .signers([owner])
+          .rpc();
+
+        try {
+          await transferBetween(source, target, 100000);
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("QuestFrozen");
+        }
+      });
+
+      it("should not move funds while claims are paused", async () => {
+        const source = await createTestQuest(
+          "transfer-claims-paused-source",
+          new anchor.BN(500000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        );
+        const target = await createTestQuest(
+          "transfer-claims-paused-target",
+          new anchor.BN(200000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        );
+        await program.methods
+          .pauseClaims()
+          .accounts({ owner: owner.publicKey, globalState: globalStatePDA })
+          .signers([owner])
+          .rpc();
+        try {
+          await transferBetween(source, target, 100000);
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("ClaimsPaused");
+        } finally {
+          await program.methods
+            .unpauseClaims()
+            .accounts({ owner: owner.publicKey, globalState: globalStatePDA })
+            .signers([owner])
+            .rpc();
+        }
+      });
+    });
+
+    describe("reward reason", () => {
+      it("should record whether a payout was a plain or weighted send", async () => {
+        const { quest, escrow } = await createTestQuest(
+          "reward-reason-quest",
+          new anchor.BN(600000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        );
+        const plain = await createTestWinner();
+        await sendTestReward(
+          quest,
+          escrow,
+          plain.winner.publicKey,
+          plain.winnerTokenAccount,
+          new anchor.BN(100000)
+        );
+
+        const weighted = await createTestWinner();
+        await program.methods
+          .sendRewardWeighted(new anchor.BN(1), new anchor.BN(2), new anchor.BN(200000))
+          .accounts({
+            owner: owner.publicKey,
+            payer: owner.publicKey,
+            globalState: globalStatePDA,
+            quest,
+            escrowAccount: escrow,
+            winner: weighted.winner.publicKey,
+            winnerTokenAccount: weighted.winnerTokenAccount,
+            rewardClaimed: rewardClaimedPDAFor(quest, weighted.winner.publicKey),
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([owner])
+          .rpc();
+
+        const reasonFor = async (winner: PublicKey) =>
+          (
+            await program.methods
+              .getWinnerReward()
+              .accounts({ rewardClaimed: rewardClaimedPDAFor(quest, winner) })
+              .view()
+          ).reason;
+        expect(await reasonFor(plain.winner.publicKey)).to.equal(0);
+        expect(await reasonFor(weighted.winner.publicKey)).to.equal(1);
+      });
+    });
+
+    describe("reward claimed migration", () => {
+      // Preloaded from tests/fixtures in the pre-versioning layout
+      const legacyRecord = new PublicKey("4rU6YYWiMbDBmNEEkMc6uAVi5sygdYxyCbwhhJNu3JNX");
+      const migrate = (caller: Keypair = owner) =>
+        program.methods
+          .migrateRewardClaimed()
+          .accounts({
+            owner: caller.publicKey,
+            globalState: globalStatePDA,
+            rewardClaimed: legacyRecord,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([caller])
+          .rpc();
+
+      it("should only let the owner migrate records", async () => {
+        const { winner: stranger } = await createTestWinner();
+        try {
+          await migrate(stranger);
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("UnauthorizedConfigUpdate");
+        }
+      });
+
+      it("should upgrade an old-layout record in place", async () => {
+        const quest = new PublicKey("5v9LZMoZgShaNbj3XX3WkpfShsMfW9adn5MwdwNnxL6D");
+        const winner = new PublicKey("7SyhJsu7wrjrbPse4eM7SYg5PpLrGj3pLr5kHAZYTt3i");
+        const [, bump] = PublicKey.findProgramAddressSync(
+          [Buffer.from("reward_claimed"), quest.toBuffer(), winner.toBuffer()],
+          program.programId
+        );
+
+        await migrate();
+
+        const record = await program.account.rewardClaimed.fetch(legacyRecord);
+        expect(record.quest.toString()).to.equal(quest.toString());
+        expect(record.winner.toString()).to.equal(winner.toString());
+        expect(record.rewardAmount.toNumber()).to.equal(120000);
+        expect(record.claimed).to.be.true;
+        expect(record.bump).to.equal(bump);
+        expect(record.reason).to.equal(0);
+        expect(record.version).to.equal(1);
+
+        try {
+          await migrate();
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("AlreadyMigrated");
+        }
+      });
+    });
+
+    describe("account layout migration", () => {
+      // Preloaded from tests/fixtures in the original Quest layout
+      const legacyQuest = new PublicKey("EBNKWmKFiVAeBY87X2EG6F6UNwTiAiYQjftreehNu3w8");
+      const migrateQuest = (caller: Keypair = owner) =>
+        program.methods
+          .migrateQuest()
+          .accounts({
+            owner: caller.publicKey,
+            globalState: globalStatePDA,
+            quest: legacyQuest,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([caller])
+          .rpc();
+
+      it("should leave a current global state alone", async () => {
+        try {
+          await program.methods
+            .migrateGlobalState()
+            .accounts({
+              owner: owner.publicKey,
+              globalState: globalStatePDA,
+              systemProgram: SystemProgram.programId,
+            })
+            .signers([owner])
+            .rpc();
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("AlreadyMigrated");
+        }
+      });
+
+      it("should only let the owner migrate quests", async () => {
+        const { winner: stranger } = await createTestWinner();
+        try {
+          await migrateQuest(stranger);
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("UnauthorizedConfigUpdate");
+        }
+      });
+
+      it("should grow an original-layout quest in place", async () => {
+        // A maximum-length id leaves no slack for the fields added since
+        let decodedBeforeMigration = true;
+        try {
+          await program.account.quest.fetch(legacyQuest);
+        } catch {
+          decodedBeforeMigration = false;
+        }
+        expect(decodedBeforeMigration).to.be.false;
+
+        await migrateQuest();
+
+        const quest = await program.account.quest.fetch(legacyQuest);
+        expect(quest.id).to.equal("legacy-layout-quest-0123456789abcdef");
+        expect(quest.amount.toNumber()).to.equal(500000);
+        expect(quest.totalWinners).to.equal(2);
+        expect(quest.totalRewardDistributed.toNumber()).to.equal(200000);
+        expect(quest.maxWinners).to.equal(5);
+        expect(quest.isActive).to.be.true;
+        expect(quest.paused).to.be.false;
+        expect(quest.leftoverPolicy).to.deep.equal({ refund: {} });
+        expect(quest.recordsClosed).to.be.false;
+
+        try {
+          await migrateQuest();
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("AlreadyMigrated");
+        }
+      });
+    });
+
+    describe("check winner claimed batch", () => {
+      it("should report a claim flag per quest, treating missing records as unclaimed", async () => {
+        const { winner, winnerTokenAccount } = await createTestWinner();
+        const quests: PublicKey[] = [];
+        for (const [i, paid] of [true, false, true].entries()) {
+          const { quest, escrow } = await createTestQuest(
+            `claimed-batch-quest-${i}`,
+            new anchor.BN(300000),
+            new anchor.BN(Date.now() / 1000 + 86400),
+            3
+          );
+          if (paid) {
+            await sendTestReward(
+              quest,
+              escrow,
+              winner.publicKey,
+              winnerTokenAccount,
+              new anchor.BN(100000)
+            );
+          }
+          quests.push(quest);
+        }
+
+        const claimed = await program.methods
+          .checkWinnerClaimedBatch(quests)
+          .accounts({ winner: winner.publicKey })
+          .remainingAccounts(
+            quests.map((quest) => ({
+              pubkey: rewardClaimedPDAFor(quest, winner.publicKey),
+              isWritable: false,
+              isSigner: false,
+            }))
+          )
+          .view();
+        expect(claimed).to.deep.equal([true, false, true]);
+      });
+    });
+
+    describe("quest lifecycle", () => {
+      const ownerTokenAccount = () =>
+        getAssociatedTokenAddress(supportedTokenMint.publicKey, owner.publicKey);
+      const setStatus = (quest: PublicKey, isActive: boolean) =>
+        program.methods
+          .updateQuestStatus(isActive)
+          .accounts({ owner: owner.publicKey, globalState: globalStatePDA, quest })
+          .signers([owner])
+          .rpc();
+      const cancel = async (quest: PublicKey, escrow: PublicKey) =>
+        program.methods
+          .cancelQuest()
+          .accounts({
+            creator: owner.publicKey,
+            globalState: globalStatePDA,
+            quest,
+            escrowAccount: escrow,
+            creatorTokenAccount: await ownerTokenAccount(),
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .signers([owner])
+          .rpc();
+      const expectInvalidTransition = async (tx: Promise<string>) => {
+        try {
+          await tx;
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("InvalidStateTransition");
+        }
+      };
+
+      it("should move between active and inactive", async () => {
+        const { quest } = await createTestQuest(
+          "lifecycle-toggle-quest",
+          new anchor.BN(300000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        );
+        await setStatus(quest, false);
+        expect((await program.account.quest.fetch(quest)).isActive).to.be.false;
+        await setStatus(quest, true);
+        expect((await program.account.quest.fetch(quest)).isActive).to.be.true;
+      });
+
+      it("should cancel, then only come back through reactivation", async () => {
+        const { quest, escrow } = await createTestQuest(
+          "lifecycle-cancel-quest",
+          new anchor.BN(300000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        );
+        await cancel(quest, escrow);
+        await expectInvalidTransition(setStatus(quest, true));
+        await expectInvalidTransition(setStatus(quest, false));
+
+        await program.methods
+          .reactivateQuest(new anchor.BN(200000))
+          .accounts({
+            creator: owner.publicKey,
+            globalState: globalStatePDA,
+            quest,
+            escrowAccount: escrow,
+            creatorTokenAccount: await ownerTokenAccount(),
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .signers([owner])
+          .rpc();
+        const questAccount = await program.account.quest.fetch(quest);
+        expect(questAccount.isActive).to.be.true;
+        expect(questAccount.cancelled).to.be.false;
+      });
+
+      it("should complete an inactive quest and keep it completed", async () => {
+        const { quest, escrow } = await createTestQuest(
+          "lifecycle-complete-quest",
+          new anchor.BN(300000),
+          new anchor.BN(Date.now() / 1000 - 8 * 86400),
+          3
+        );
+        await setStatus(quest, false);
+        await program.methods
+          .claimRemainingReward()
+          .accounts({
+            claimer: owner.publicKey,
+            globalState: globalStatePDA,
+            quest,
+            escrowAccount: escrow,
+            creatorTokenAccount: await ownerTokenAccount(),
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .signers([owner])
+          .rpc();
+        expect((await program.account.quest.fetch(quest)).finalized).to.be.true;
+
+        await expectInvalidTransition(setStatus(quest, true));
+        await expectInvalidTransition(setStatus(quest, false));
+      });
+    });
+
+    describe("batch preview", () => {
+      const preview = (quest: PublicKey, escrowAccount: PublicKey, amounts: number[]) =>
+        program.methods
+          .previewBatch(amounts.map((amount) => new anchor.BN(amount)))
+          .accounts({ globalState: globalStatePDA, quest, escrowAccount })
+          .view();
+
+      it("should report a batch that fits and one that completes the quest", async () => {
+        const { quest, escrow } = await createTestQuest(
+          "batch-preview-quest",
+          new anchor.BN(500000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        );
+
+        const fitting = await preview(quest, escrow, [100000, 150000]);
+        expect(fitting.total.toNumber()).to.equal(250000);
+        expect(fitting.fitsEscrow).to.be.true;
+        expect(fitting.fitsSlots).to.be.true;
+        expect(fitting.wouldComplete).to.be.false;
+
+        const completing = await preview(quest, escrow, [100000, 100000, 100000]);
+        expect(completing.fitsEscrow).to.be.true;
+        expect(completing.fitsSlots).to.be.true;
+        expect(completing.wouldComplete).to.be.true;
+
+        // Previewing never touches the quest
+        const questAccount = await program.account.quest.fetch(quest);
+        expect(questAccount.totalWinners).to.equal(0);
+        expect(questAccount.totalRewardDistributed.toNumber()).to.equal(0);
+      });
+
+      it("should flag a batch over the quest's funds and winner slots", async () => {
+        const { quest, escrow } = await createTestQuest(
+          "batch-preview-over-quest",
+          new anchor.BN(500000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        );
+
+        const over = await preview(quest, escrow, [200000, 200000, 100000, 100000]);
+        expect(over.total.toNumber()).to.equal(600000);
+        expect(over.fitsEscrow).to.be.false;
+        expect(over.fitsSlots).to.be.false;
+        expect(over.wouldComplete).to.be.false;
+      });
+
+      it("should apply the early bonus and keep the reserve locked", async () => {
+        const { quest, escrow } = await createTestQuest(
+          "batch-preview-bonus-quest",
+          new anchor.BN(500000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        );
+        await program.methods
+          .setQuestBonus(1000, new anchor.BN(Date.now() / 1000 + 3600))
+          .accounts({ creator: owner.publicKey, quest })
+          .signers([owner])
+          .rpc();
+        await program.methods
+          .setReserveAmount(new anchor.BN(100000))
+          .accounts({ creator: owner.publicKey, quest })
+          .signers([owner])
+          .rpc();
+
+        // 400000 plus the 10% bonus dips into the reserve
+        const boosted = await preview(quest, escrow, [200000, 200000]);
+        expect(boosted.total.toNumber()).to.equal(440000);
+        expect(boosted.fitsEscrow).to.be.false;
+      });
+
+      it("should reject a batch send_reward_batch would reject", async () => {
+        const { quest, escrow } = await createTestQuest(
+          "batch-preview-oversized-quest",
+          new anchor.BN(500000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          10
+        );
+        try {
+          await preview(quest, escrow, new Array(8).fill(10000));
+          expect.fail("Expected the view to fail");
+        } catch (error) {
+          expect(error.toString()).to.include("BatchTooLarge");
+        }
+      });
+    });
+
+    describe("min reward feasibility", () => {
+      const setMinReward = (minReward: number) =>
+        program.methods
+          .setGlobalMinReward(new anchor.BN(minReward))
+          .accounts({ owner: owner.publicKey, globalState: globalStatePDA })
+          .signers([owner])
+          .rpc();
+      const setStrict = (enabled: boolean) =>
+        program.methods
+          .setEnforceMinRewardFeasibility(enabled)
+          .accounts({ owner: owner.publicKey, globalState: globalStatePDA })
+          .signers([owner])
+          .rpc();
+
+      before(async () => {
+        await setMinReward(50000);
+        await setStrict(true);
+      });
+
+      after(async () => {
+        await setStrict(false);
+        await setMinReward(0);
+      });
+
+      it("should pay a winner when the open slots can still get the minimum", async () => {
+        const { quest, escrow } = await createTestQuest(
+          "feasible-min-reward-quest",
+          new anchor.BN(300000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        );
+        const { winner, winnerTokenAccount } = await createTestWinner();
+        // 100000 left covers two more winners at 50000
+        await sendTestReward(
+          quest,
+          escrow,
+          winner.publicKey,
+          winnerTokenAccount,
+          new anchor.BN(200000)
+        );
+        expect((await program.account.quest.fetch(quest)).totalWinners).to.equal(1);
+      });
+
+      it("should reject a payout that strands the open slots below the minimum", async () => {
+        const { quest, escrow } = await createTestQuest(
+          "infeasible-min-reward-quest",
+          new anchor.BN(300000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        );
+        const { winner, winnerTokenAccount } = await createTestWinner();
+        try {
+          // 50000 left cannot cover two more winners at 50000
+          await sendTestReward(
+            quest,
+            escrow,
+            winner.publicKey,
+            winnerTokenAccount,
+            new anchor.BN(250000)
+          );
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("MinRewardInfeasible");
+        }
+
+        // The same payout only warns once the check is relaxed
+        await setStrict(false);
+        try {
+          await sendTestReward(
+            quest,
+            escrow,
+            winner.publicKey,
+            winnerTokenAccount,
+            new anchor.BN(250000)
+          );
+        } finally {
+          await setStrict(true);
+        }
+        expect((await program.account.quest.fetch(quest)).totalWinners).to.equal(1);
+      });
+    });
+
+    describe("owner history", () => {
+      const setOwner = (currentOwner: Keypair, newOwner: PublicKey) =>
+        program.methods
+          .setOwner(newOwner)
+          .accounts({ currentOwner: currentOwner.publicKey, globalState: globalStatePDA })
+          .signers([currentOwner])
+          .rpc();
+
+      it("should record each outgoing owner", async () => {
+        const interimOwner = Keypair.generate();
+        const signature = await provider.connection.requestAirdrop(
+          interimOwner.publicKey,
+          anchor.web3.LAMPORTS_PER_SOL
+        );
+        await provider.connection.confirmTransaction(signature);
+
+        await setOwner(owner, interimOwner.publicKey);
+        await setOwner(interimOwner, owner.publicKey);
+
+        const history = await program.methods
+          .getOwnerHistory()
+          .accounts({ globalState: globalStatePDA })
+          .view();
+        expect(history.slice(-2).map((key: PublicKey) => key.toString())).to.deep.equal([
+          owner.publicKey.toString(),
+          interimOwner.publicKey.toString(),
+        ]);
+        const globalState = await program.account.globalState.fetch(globalStatePDA);
+        expect(globalState.owner.toString()).to.equal(owner.publicKey.toString());
+      });
+    });
+
+    describe("quest id length limit", () => {
+      const setMaxQuestIdLength = (maxLength: number) =>
+        program.methods
+          .setMaxQuestIdLength(maxLength)
+          .accounts({ owner: owner.publicKey, globalState: globalStatePDA })
+          .signers([owner])
+          .rpc();
+
+      after(async () => {
+        await setMaxQuestIdLength(36);
+      });
+
+      it("should enforce a soft limit below the hard cap", async () => {
+        await setMaxQuestIdLength(10);
+        try {
+          await createTestQuest(
+            "id-limit-11",
+            new anchor.BN(300000),
+            new anchor.BN(Date.now() / 1000 + 86400),
+            3
+          );
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("QuestIdTooLong");
+        }
+
+        const { quest } = await createTestQuest(
+          "id-limit10",
+          new anchor.BN(300000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        );
+        expect((await program.account.quest.fetch(quest)).id).to.equal("id-limit10");
+      });
+
+      it("should allow the limit up to, but not past, the hard cap", async () => {
+        await setMaxQuestIdLength(36);
+        const id = "x".repeat(36);
+        const { quest } = await createTestQuest(
+          id,
+          new anchor.BN(300000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        );
+        expect((await program.account.quest.fetch(quest)).id).to.equal(id);
+
+        try {
+          await setMaxQuestIdLength(37);
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("InvalidConfigValue");
+        }
+      });
+    });
+
+    describe("escrow shortfall", () => {
+      const shortfall = async (quest: PublicKey, escrowAccount: PublicKey) =>
+        (
+          await program.methods
+            .getEscrowShortfall()
+            .accounts({ quest, escrowAccount })
+            .view()
+        ).toNumber();
+
+      it("should be zero for an exactly backed quest", async () => {
+        const { quest, escrow } = await createTestQuest(
+          "exact-shortfall-quest",
+          new anchor.BN(300000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        );
+        expect(await shortfall(quest, escrow)).to.equal(0);
+      });
+
+      it("should be positive when the escrow holds a surplus", async () => {
+        const { quest, escrow } = await createTestQuest(
+          "surplus-shortfall-quest",
+          new anchor.BN(300000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        );
+        await transfer(
+          provider.connection,
+          owner,
+          await getAssociatedTokenAddress(supportedTokenMint.publicKey, owner.publicKey),
+          escrow,
+          owner,
+          25000
+        );
+        expect(await shortfall(quest, escrow)).to.equal(25000);
+      });
+
+      it("should be negative when the escrow is short", async () => {
+        // Preloaded from tests/fixtures: 500000 owed, 200000 in escrow
+        expect(
+          await shortfall(
+            new PublicKey("4qXFMLTggwQDnqTm6wSpWFiSX7eb65xy4rjjPbQjtuqP"),
+            new PublicKey("G64zGzDHcFkfif93JPYTKfDvtwwj1iEZhboQXVfHMx8k")
+          )
+        ).to.equal(-300000);
+      });
+    });
+
+    describe("distribute remaining equally", () => {
+      const splitAccounts = (
+        quest: PublicKey,
+        winners: { winner: Keypair; winnerTokenAccount: PublicKey }[]
+      ) =>
+        winners.flatMap(({ winner, winnerTokenAccount }) => [
+          { pubkey: winnerTokenAccount, isWritable: true, isSigner: false },
+          {
+            pubkey: rewardClaimedPDAFor(quest, winner.publicKey),
+            isWritable: true,
+            isSigner: false,
+          },
+        ]);
+      const distribute = (
+        quest: PublicKey,
+        escrow: PublicKey,
+        winners: { winner: Keypair; winnerTokenAccount: PublicKey }[]
+      ) =>
+        program.methods
+          .distributeRemainingEqually(winners.map(({ winner }) => winner.publicKey))
+          .accounts({
+            owner: owner.publicKey,
+            globalState: globalStatePDA,
+            quest,
+            escrowAccount: escrow,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .remainingAccounts(splitAccounts(quest, winners))
+          .signers([owner])
+          .rpc();
+
+      it("should split the remainder evenly, giving the dust to the first winner", async () => {
+        const { quest, escrow } = await createTestQuest(
+          "equal-split-quest",
+          new anchor.BN(1000000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          5
+        );
+        const early = await createTestWinner();
+        await sendTestReward(
+          quest,
+          escrow,
+          early.winner.publicKey,
+          early.winnerTokenAccount,
+          new anchor.BN(99999)
+        );
+
+        const winners = [
+          await createTestWinner(),
+          await createTestWinner(),
+          await createTestWinner(),
+        ];
+        await distribute(quest, escrow, winners);
+
+        const balances = await Promise.all(
+          winners.map(
+            async ({ winnerTokenAccount }) =>
+              Number((await getAccount(provider.connection, winnerTokenAccount)).amount)
+          )
+        );
+        expect(balances).to.deep.equal([300001, 300000, 300000]);
+        expect(Number((await getAccount(provider.connection, escrow)).amount)).to.equal(0);
+
+        const questAccount = await program.account.quest.fetch(quest);
+        expect(questAccount.totalWinners).to.equal(4);
+        expect(questAccount.totalRewardDistributed.toNumber()).to.equal(1000000);
+        expect(questAccount.finalized).to.be.true;
+        expect(questAccount.isActive).to.be.false;
+      });
+
+      it("should reject more winners than the quest has slots left", async () => {
+        const { quest, escrow } = await createTestQuest(
+          "equal-split-over-quest",
+          new anchor.BN(300000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          2
+        );
+        const winners = [
+          await createTestWinner(),
+          await createTestWinner(),
+          await createTestWinner(),
+        ];
+        try {
+          await distribute(quest, escrow, winners);
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("MaxWinnersReached");
+        }
+      });
+    });
+
+    describe("program id winners", () => {
+      it("should reject the system program as winner", async () => {
+        const { quest, escrow } = await createTestQuest(
+          "program-winner-quest",
+          new anchor.BN(1000000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          5
+        );
+        const winner = SystemProgram.programId;
+        const winnerTokenAccount = await getAssociatedTokenAddress(
+          supportedTokenMint.publicKey,
+          winner,
+          true
+        );
+        await provider.sendAndConfirm(
+          new Transaction().add(
+            createAssociatedTokenAccountInstruction(
+              owner.publicKey,
+              winnerTokenAccount,
+              winner,
+              supportedTokenMint.publicKey
+            )
+          ),
+          [owner]
+        );
+
+        try {
+          await sendTestReward(
+            quest,
+            escrow,
+            winner,
+            winnerTokenAccount,
+            new anchor.BN(100000)
+          );
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("InvalidWinner");
+        }
+      });
+    });
+
+    // Runs last: once the log exists every privileged instruction must pass it
+    describe("audit log", () => {
+      const [auditLogPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("audit_log")],
+        program.programId
+      );
+
+      before(async () => {
+        await program.methods
+          .initializeAuditLog()
+          .accounts({
+            owner: owner.publicKey,
+            globalState: globalStatePDA,
+            auditLog: auditLogPDA,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([owner])
+          .rpc();
+      });
+
+      const readAuditLog = () =>
+        program.methods.getAuditLog().accounts({ auditLog: auditLogPDA }).view();
+
+      it("should record pause and unpause", async () => {
+        for (const method of [program.methods.pause, program.methods.unpause]) {
+          await method()
+            .accounts({
+              owner: owner.publicKey,
+              globalState: globalStatePDA,
+              auditLog: auditLogPDA,
+            })
+            .signers([owner])
+            .rpc();
+        }
+
+        // Initializing the log is its first entry
+        const entries = await readAuditLog();
+        expect(entries.map((e) => e.actionCode)).to.deep.equal([26, 1, 2]);
+        for (const entry of entries) {
+          expect(entry.actor.toString()).to.equal(owner.publicKey.toString());
+          expect(entry.timestamp.toNumber()).to.be.greaterThan(0);
+        }
+      });
+
+      it("should require the log once it is initialized", async () => {
+        const config = await program.methods
+          .getConfig()
+          .accounts({ globalState: globalStatePDA })
+          .view();
+        expect(config.auditLogInitialized).to.be.true;
+
+        try {
+          await program.methods
+            .pause()
+            .accounts({ owner: owner.publicKey, globalState: globalStatePDA })
+            .signers([owner])
+            .rpc();
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("AuditLogRequired");
+        }
+
+        const { quest, escrow } = await createTestQuest(
+          "audit-log-quest",
+          new anchor.BN(500000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        );
+        const { winner, winnerTokenAccount } = await createTestWinner();
+        try {
+          await sendTestReward(
+            quest,
+            escrow,
+            winner.publicKey,
+            winnerTokenAccount,
+            new anchor.BN(10000)
+          );
+          expect.fail("Expected the transaction to fail");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("AuditLogRequired");
+        }
+
+        await program.methods
+          .sendReward(new anchor.BN(10000))
+          .accounts({
+            owner: owner.publicKey,
+            payer: owner.publicKey,
+            globalState: globalStatePDA,
+            quest,
+            escrowAccount: escrow,
+            winner: winner.publicKey,
+            winnerTokenAccount,
+            rewardClaimed: rewardClaimedPDAFor(quest, winner.publicKey),
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+            auditLog: auditLogPDA,
+          })
+          .signers([owner])
+          .rpc();
+
+        const entries = await readAuditLog();
+        expect(entries).to.have.length(4);
+        expect(entries[3].actionCode).to.equal(4);
+      });
+
+      it("should record quest freezes, config updates and blocklist changes", async () => {
+        const { quest } = await createTestQuest(
+          "audit-log-freeze-quest",
+          new anchor.BN(500000),
+          new anchor.BN(Date.now() / 1000 + 86400),
+          3
+        );
+        for (const method of [
+          program.methods.ownerFreezeQuest,
+          program.methods.ownerUnfreezeQuest,
+        ]) {
+          await method()
+            .accounts({
+              owner: owner.publicKey,
+              globalState: globalStatePDA,
+              quest,
+              auditLog: auditLogPDA,
+            })
+            .signers([owner])
+            .rpc();
+        }
+
+        await program.methods
+          .setEnforceSupportedMintOnPayout(false)
+          .accounts({
+            owner: owner.publicKey,
+            globalState: globalStatePDA,
+            auditLog: auditLogPDA,
+          })
+          .signers([owner])
+          .rpc();
+
+        const address = Keypair.generate().publicKey;
+        for (const method of [
+          program.methods.addBlockedAddress,
+          program.methods.removeBlockedAddress,
+        ]) {
+          await method(address)
+            .accounts({
+              owner: owner.publicKey,
+              globalState: globalStatePDA,
+              systemProgram: SystemProgram.programId,
+              auditLog: auditLogPDA,
+            })
+            .signers([owner])
+            .rpc();
+        }
+
+        const entries = await readAuditLog();
+        expect(entries.slice(-5).map((e) => e.actionCode)).to.deep.equal([
+          13, 14, 17, 19, 20,
+        ]);
+      });
+
+      it("should record the owner claiming a quest's remainder", async () => {
+        const { quest, escrow } = await createTestQuest(
+          "audit-log-claim-quest",
+          new anchor.BN(500000),
+          new anchor.BN(Date.now() / 1000 - 8 * 86400),
+          3
+        );
+        await program.methods
+          .updateQuestStatus(false)
+          .accounts({
+            owner: owner.publicKey,
+            globalState: globalStatePDA,
+            quest,
+            auditLog: auditLogPDA,
+          })
+          .signers([owner])
+          .rpc();
+        await program.methods
+          .claimRemainingReward()
+          .accounts({
+            claimer: owner.publicKey,
+            globalState: globalStatePDA,
+            quest,
+            escrowAccount: escrow,
+            creatorTokenAccount: await getAssociatedTokenAddress(
+              supportedTokenMint.publicKey,
+              owner.publicKey
+            ),
+            tokenProgram: TOKEN_PROGRAM_ID,
+            auditLog: auditLogPDA,
+          })
+          .signers([owner])
+          .rpc();
+
+        const entries = await readAuditLog();
+        expect(entries.slice(-2).map((e) => e.actionCode)).to.deep.equal([10, 24]);
+      });
+    });
   });
 });